@@ -0,0 +1,140 @@
+//! Module containing utility functions for `anyhow` results.
+
+use std::{fmt::Display, thread, time::Duration};
+
+use anyhow::{bail, Context, Result};
+
+use crate::warning;
+
+/// Maximum number of error messages reported by `collect_results`.
+const MAX_REPORTED_ERRORS: usize = 3;
+
+/// Trait to wrap a value into an `anyhow` result.
+pub trait IntoResult: Sized {
+    /// Wrap a value as the success of a result.
+    fn into_ok(self) -> Result<Self> {
+        Ok(self)
+    }
+
+    /// Wrap an error as the failure of a result.
+    fn into_err<T>(self) -> Result<T>
+    where
+        Self: std::error::Error + Send + Sync + 'static,
+    {
+        Err(anyhow::Error::new(self))
+    }
+}
+
+impl<S> IntoResult for S {}
+
+/// Trait containing utility functions for the `Result` data structure.
+pub trait ResultExt<T> {
+    /// Convert a result to an option, printing a warning if it is an error.
+    fn ok_or_warn(self) -> Option<T>;
+}
+
+impl<T, E: Display> ResultExt<T> for std::result::Result<T, E> {
+    fn ok_or_warn(self) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warning!("{}", err);
+                None
+            }
+        }
+    }
+}
+
+/// Attach a context message to the error of a result.
+pub fn with_context<T>(r: Result<T>, msg: &str) -> Result<T> {
+    r.context(msg.to_owned())
+}
+
+/// Combine many results into a result of all success values.
+///
+/// Return an error reporting the number of failures and the first few error
+/// messages if any of the results fails.
+pub fn collect_results<T>(results: Vec<Result<T>>) -> Result<Vec<T>> {
+    let num_results = results.len();
+    let mut values = vec![];
+    let mut errors = vec![];
+    for res in results {
+        match res {
+            Ok(value) => values.push(value),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(values);
+    }
+    let messages = errors
+        .iter()
+        .take(MAX_REPORTED_ERRORS)
+        .map(|err| format!("  - {}", err))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let more = match errors.len() > MAX_REPORTED_ERRORS {
+        true => {
+            format!("\n  - and {} more", errors.len() - MAX_REPORTED_ERRORS)
+        }
+        false => "".to_owned(),
+    };
+    bail!(
+        "{} of {} results failed:\n{}{}",
+        errors.len(),
+        num_results,
+        messages,
+        more
+    )
+}
+
+/// Run a function until it succeeds, for at most `attempts` times, waiting for
+/// a fixed `delay` between attempts.
+///
+/// Return the last error if all attempts fail. The function is run at least
+/// once even if `attempts` is 0.
+pub fn retry<T, F>(attempts: usize, delay: Duration, f: F) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    retry_with_delays(attempts, f, |_| delay)
+}
+
+/// Run a function until it succeeds, for at most `attempts` times, waiting for
+/// an exponentially increasing delay between attempts, starting from
+/// `initial_delay`.
+///
+/// Return the last error if all attempts fail. The function is run at least
+/// once even if `attempts` is 0.
+pub fn retry_with_backoff<T, F>(
+    attempts: usize,
+    initial_delay: Duration,
+    f: F,
+) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    retry_with_delays(attempts, f, |attempt| {
+        let factor = 2u32.saturating_pow(attempt as u32);
+        initial_delay.saturating_mul(factor)
+    })
+}
+
+/// Run a function until it succeeds, for at most `attempts` times, waiting for
+/// `delay(n)` after the failed attempt `n`, indexed from 0.
+fn retry_with_delays<T, F, D>(attempts: usize, mut f: F, delay: D) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+    D: Fn(usize) -> Duration,
+{
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Ok(res) => return Ok(res),
+            Err(err) if attempt + 1 >= attempts => return Err(err),
+            Err(_) => thread::sleep(delay(attempt)),
+        }
+        attempt += 1;
+    }
+}
@@ -1,6 +1,6 @@
 //! Core command line arguments for all tools
 
-use clap::Arg;
+use clap::{Arg, ArgMatches, Command};
 
 /// Trait to create a new argument
 pub trait ArgExt<'a> {
@@ -13,3 +13,42 @@ impl<'a> ArgExt<'a> for Arg<'a> {
         Arg::new(name).long(name)
     }
 }
+
+/// Trait to register subcommands to a command
+pub trait SubcommandCli<'a> {
+    /// Add a subcommand, whose arguments are configured by `configure`
+    fn add_subcommand(
+        self,
+        name: &'a str,
+        about: &'a str,
+        configure: impl FnOnce(Command<'a>) -> Command<'a>,
+    ) -> Self;
+}
+
+impl<'a> SubcommandCli<'a> for Command<'a> {
+    fn add_subcommand(
+        self,
+        name: &'a str,
+        about: &'a str,
+        configure: impl FnOnce(Command<'a>) -> Command<'a>,
+    ) -> Self {
+        self.subcommand(configure(Command::new(name).about(about)))
+    }
+}
+
+/// Handler of a subcommand, taking the argument matches of that subcommand
+pub type SubcommandHandler<'a, T> = &'a dyn Fn(&ArgMatches) -> T;
+
+/// Run the handler of the matched subcommand, if any
+///
+/// Handlers are paired with the names of their subcommands.
+pub fn dispatch_subcommand<T>(
+    argms: &ArgMatches,
+    handlers: &[(&str, SubcommandHandler<T>)],
+) -> Option<T> {
+    let (name, sub_argms) = argms.subcommand()?;
+    handlers
+        .iter()
+        .find(|(handler_name, _)| *handler_name == name)
+        .map(|(_, handler)| handler(sub_argms))
+}
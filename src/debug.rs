@@ -1,13 +1,133 @@
 //! Module to store mutable flags, used by all packages
 
-/// Global variable which enables the printing of debugging message.
-pub static mut DEBUG_MODE: bool = true;
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+};
 
-/// Global variable which enables the printing of deep debugging message.
-pub static mut DEEP_DEBUG_MODE: bool = true;
+use anyhow::{bail, Error};
 
-/// Global variable which disables all printing functions.
-pub static mut DISABLE_PRINTING: bool = false;
+/// Global flag which enables the printing of debugging message.
+static DEBUG_MODE: AtomicBool = AtomicBool::new(true);
+
+/// Global flag which enables the printing of deep debugging message.
+static DEEP_DEBUG_MODE: AtomicBool = AtomicBool::new(true);
+
+/// Global flag which disables all printing functions.
+static DISABLE_PRINTING: AtomicBool = AtomicBool::new(false);
+
+/// Global flag which makes commands and file writes only be logged, but not
+/// performed.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
 
 /// Global variable storing the debugging marker length in debug mode.
-pub static mut DEBUG_MARKER_LEN: usize = 0;
+static DEBUG_MARKER_LEN: AtomicUsize = AtomicUsize::new(0);
+
+/// Global variable storing the color choice, encoded by `ColorChoice as u8`.
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Choices of colorizing printed messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colorize only when stdout is a terminal.
+    Auto,
+
+    /// Always colorize.
+    Always,
+
+    /// Never colorize.
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => bail!("Invalid color choice: {}", s),
+        }
+    }
+}
+
+/// Check if the printing of debugging message is enabled.
+pub fn get_debug_mode() -> bool {
+    DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+/// Enable or disable the printing of debugging message.
+pub fn set_debug_mode(value: bool) {
+    DEBUG_MODE.store(value, Ordering::Relaxed)
+}
+
+/// Check if the printing of deep debugging message is enabled.
+pub fn get_deep_debug_mode() -> bool {
+    DEEP_DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+/// Enable or disable the printing of deep debugging message.
+pub fn set_deep_debug_mode(value: bool) {
+    DEEP_DEBUG_MODE.store(value, Ordering::Relaxed)
+}
+
+/// Check if all printing functions are disabled.
+pub fn get_disable_printing() -> bool {
+    DISABLE_PRINTING.load(Ordering::Relaxed)
+}
+
+/// Disable or enable all printing functions.
+pub fn set_disable_printing(value: bool) {
+    DISABLE_PRINTING.store(value, Ordering::Relaxed)
+}
+
+/// Check if the dry-run mode is enabled.
+///
+/// In this mode, running commands and writing files only log the intended
+/// actions, while reading files still happens normally.
+pub fn get_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Enable or disable the dry-run mode.
+pub fn set_dry_run(value: bool) {
+    DRY_RUN.store(value, Ordering::Relaxed)
+}
+
+/// Get the debugging marker length in debug mode.
+pub fn get_debug_marker_len() -> usize {
+    DEBUG_MARKER_LEN.load(Ordering::Relaxed)
+}
+
+/// Set the debugging marker length in debug mode.
+pub fn set_debug_marker_len(value: usize) {
+    DEBUG_MARKER_LEN.store(value, Ordering::Relaxed)
+}
+
+/// Get the color choice of printed messages.
+pub fn get_color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        c if c == ColorChoice::Always as u8 => ColorChoice::Always,
+        c if c == ColorChoice::Never as u8 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// Set the color choice of printed messages.
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice as u8, Ordering::Relaxed)
+}
+
+/// Check if printed messages should be colorized.
+///
+/// In the `Auto` mode, messages are colorized only when stdout is a terminal.
+/// This is consulted by `report::colorize` for warnings, errors and panic
+/// messages.
+pub fn should_colorize() -> bool {
+    match get_color_choice() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => termsize::terminal_size().is_some(),
+    }
+}
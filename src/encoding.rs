@@ -0,0 +1,71 @@
+//! Module containing utility functions to encode and decode data.
+
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+
+/// Encode bytes to a lowercase hexadecimal string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        let _ = write!(res, "{:02x}", byte);
+    }
+    res
+}
+
+/// Decode a hexadecimal string, in lowercase or uppercase, to bytes.
+///
+/// Return an error if the string has an odd length or a non-hexadecimal
+/// character, whose byte offset is reported.
+pub fn from_hex(s: &str) -> Result<Vec<u8>> {
+    if let Some(offset) = s.bytes().position(|c| !c.is_ascii()) {
+        bail!("Invalid non-ASCII hex character at offset: {}", offset)
+    }
+
+    if s.len() % 2 != 0 {
+        bail!("Invalid hex string of odd length: {}", s.len())
+    }
+
+    /// Decode a hexadecimal digit at a byte offset.
+    fn decode_digit(c: u8, offset: usize) -> Result<u8> {
+        match (c as char).to_digit(16) {
+            Some(digit) => Ok(digit as u8),
+            None => {
+                bail!(
+                    "Invalid hex character `{}` at offset: {}",
+                    c as char,
+                    offset
+                )
+            }
+        }
+    }
+
+    s.as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(idx, pair)| {
+            let high = decode_digit(pair[0], 2 * idx)?;
+            let low = decode_digit(pair[1], 2 * idx + 1)?;
+            Ok((high << 4) | low)
+        })
+        .collect()
+}
+
+/// Encode bytes to a base64 string, using the standard alphabet with padding.
+#[cfg(feature = "base64")]
+pub fn to_base64(bytes: &[u8]) -> String {
+    base64::encode(bytes)
+}
+
+/// Decode a base64 string, using the standard alphabet with padding, to bytes.
+///
+/// Return an error if the string has an invalid character or length. The
+/// error only reports the decoder error, not the string, which may be a secret
+/// or a large blob.
+#[cfg(feature = "base64")]
+pub fn from_base64(s: &str) -> Result<Vec<u8>> {
+    match base64::decode(s) {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => bail!("Invalid base64 string: {}", err),
+    }
+}
@@ -1,13 +1,60 @@
 //! Module containing utility functions to handle files.
 
-use std::{ffi::OsStr, fs::File, io::Write, path::Path};
+use std::{
+    ffi::OsStr,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    path::{Component, Path, PathBuf},
+};
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
+use tempfile::{NamedTempFile, TempDir};
+
+use crate::{debug, encoding, info, system};
+
+/// Size of chunks when reading a file to compute its hash.
+pub const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Data structure capturing a temporary working directory.
+///
+/// The directory and all of its content are removed when the workspace is
+/// dropped, unless it is persisted by `persist`.
+pub struct TempWorkspace {
+    /// The underlying temporary directory.
+    dir: TempDir,
+}
+
+impl TempWorkspace {
+    /// Constructor, creating a new temporary directory.
+    pub fn new() -> Result<Self> {
+        match tempfile::tempdir() {
+            Ok(dir) => Ok(TempWorkspace { dir }),
+            Err(err) => bail!("Failed to create temporary directory: {}", err),
+        }
+    }
+
+    /// Get the path of the temporary directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Keep the temporary directory after the workspace is dropped.
+    ///
+    /// Return the path of the persisted directory.
+    pub fn persist(self) -> PathBuf {
+        self.dir.into_path()
+    }
+}
 
 /// Function to save a string to a temporary file of a given name.
 ///
-/// Return the output file path.
-pub fn save_to_temporary_file(content: &str, filename: &str) -> Result<String> {
+/// The file is created in a new temporary directory, which is not removed
+/// automatically. Return the output file path as a `PathBuf`.
+pub fn save_to_temporary_file_path(
+    content: &str,
+    filename: &str,
+) -> Result<PathBuf> {
     let output_dir_path = match tempfile::tempdir() {
         Ok(dir) => dir.into_path(),
         Err(err) => bail!(err),
@@ -15,19 +62,81 @@ pub fn save_to_temporary_file(content: &str, filename: &str) -> Result<String> {
     let output_file_path = output_dir_path.join(filename);
     let mut output_file = File::create(&output_file_path)?;
     match output_file.write_all(content.as_bytes()) {
-        Ok(_) => match output_file_path.to_str() {
-            Some(path) => Ok(path.to_string()),
-            None => bail!("Output file path not found!"),
-        },
+        Ok(_) => Ok(output_file_path),
         Err(err) => bail!(err),
     }
 }
 
+/// Wrapper of `save_to_temporary_file_path` returning the output file path as
+/// a `String`.
+///
+/// Return an error if the output file path is not valid UTF-8.
+pub fn save_to_temporary_file(content: &str, filename: &str) -> Result<String> {
+    let output_file_path = save_to_temporary_file_path(content, filename)?;
+    match output_file_path.to_str() {
+        Some(path) => Ok(path.to_string()),
+        None => bail!("Output file path not found!"),
+    }
+}
+
+/// Save a string to a temporary file, and call a function on its path.
+///
+/// The temporary file is deleted after the function returns, even if it
+/// returns an error.
+pub fn with_temp_file<T, F>(content: &str, f: F) -> Result<T>
+where
+    F: FnOnce(&Path) -> Result<T>,
+{
+    let mut temp_file = match NamedTempFile::new() {
+        Ok(file) => file,
+        Err(err) => bail!("Failed to create temporary file: {}", err),
+    };
+    if let Err(err) = temp_file.write_all(content.as_bytes()) {
+        let path = temp_file.path().display();
+        bail!("Failed to write file: {}: {}", path, err)
+    }
+    f(temp_file.path())
+}
+
+/// Read the whole content of a file to a string.
+pub fn read_file_to_string(path: &str) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) => bail!("Failed to read file: {}: {}", path, err),
+    }
+}
+
+/// Read the whole content of a file to a vector of bytes.
+pub fn read_file_bytes(path: &str) -> Result<Vec<u8>> {
+    match fs::read(path) {
+        Ok(content) => Ok(content),
+        Err(err) => bail!("Failed to read file: {}: {}", path, err),
+    }
+}
+
 /// Get file extension of a file name.
 pub fn get_file_ext(filename: &str) -> Option<&str> {
     Path::new(filename).extension().and_then(OsStr::to_str)
 }
 
+/// Get file stem, which is the file name without extension, of a file name.
+pub fn get_file_stem(filename: &str) -> Option<&str> {
+    Path::new(filename).file_stem().and_then(OsStr::to_str)
+}
+
+/// Replace the extension of a file name, or append one if it has none.
+///
+/// A leading dot of a file name, such as in `.bashrc`, is not considered as
+/// the start of an extension.
+pub fn change_extension(filename: &str, new_ext: &str) -> String {
+    let filename = filename.trim_end_matches(std::path::is_separator);
+    let stem = match get_file_ext(filename) {
+        Some(ext) => &filename[..filename.len() - ext.len() - 1],
+        None => filename,
+    };
+    format!("{}.{}", stem, new_ext)
+}
+
 /// Get the parent directory of a file.
 ///
 /// Return `None` if the parent directory is not found or empty.
@@ -44,3 +153,418 @@ pub fn get_parent_directory(filename: &str) -> Option<String> {
         false => Some(parent_dir.to_owned()),
     }
 }
+
+/// Check if a path exists, after resolving symbolic links.
+///
+/// Return `false` for a broken symbolic link.
+pub fn file_exists(path: &str) -> bool {
+    Path::new(path).exists()
+}
+
+/// Check if a path is a directory, after resolving symbolic links.
+pub fn is_directory(path: &str) -> bool {
+    Path::new(path).is_dir()
+}
+
+/// Check if a path is a regular file, after resolving symbolic links.
+pub fn is_regular_file(path: &str) -> bool {
+    Path::new(path).is_file()
+}
+
+/// Compute the SHA-256 hash of a string.
+///
+/// Return the digest in lowercase hexadecimal.
+pub fn hash_str_sha256(content: &str) -> String {
+    encoding::to_hex(&Sha256::digest(content.as_bytes()))
+}
+
+/// Compute the SHA-256 hash of a file, which is read by chunks of
+/// `HASH_CHUNK_SIZE` bytes.
+///
+/// Return the digest in lowercase hexadecimal.
+pub fn hash_file_sha256(path: &str) -> Result<String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => bail!("Failed to open file: {}: {}", path, err),
+    };
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; HASH_CHUNK_SIZE];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => bail!("Failed to read file: {}: {}", path, err),
+        }
+    }
+    Ok(encoding::to_hex(&hasher.finalize()))
+}
+
+/// Check if two files have the same content, which are compared by chunks of
+/// `HASH_CHUNK_SIZE` bytes, without loading the whole files into memory.
+///
+/// Return an error if either file cannot be read.
+pub fn files_equal(a: &str, b: &str) -> Result<bool> {
+    /// Open a file and get its size.
+    fn open_file(path: &str) -> Result<(BufReader<File>, u64)> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => bail!("Failed to open file: {}: {}", path, err),
+        };
+        match file.metadata() {
+            Ok(metadata) => {
+                let reader = BufReader::with_capacity(HASH_CHUNK_SIZE, file);
+                Ok((reader, metadata.len()))
+            }
+            Err(err) => bail!("Failed to read file: {}: {}", path, err),
+        }
+    }
+
+    /// Fill the buffer of a reader, retrying when interrupted.
+    fn fill_buffer<'a>(
+        reader: &'a mut BufReader<File>,
+        path: &str,
+    ) -> Result<&'a [u8]> {
+        loop {
+            match reader.fill_buf() {
+                Ok(_) => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => bail!("Failed to read file: {}: {}", path, err),
+            }
+        }
+        Ok(reader.buffer())
+    }
+
+    let (mut reader_a, size_a) = open_file(a)?;
+    let (mut reader_b, size_b) = open_file(b)?;
+    if size_a != size_b {
+        return Ok(false);
+    }
+
+    loop {
+        let buffer_a = fill_buffer(&mut reader_a, a)?;
+        let buffer_b = fill_buffer(&mut reader_b, b)?;
+        let len = buffer_a.len().min(buffer_b.len());
+        if len == 0 {
+            return Ok(buffer_a.is_empty() && buffer_b.is_empty());
+        } else if buffer_a[..len] != buffer_b[..len] {
+            return Ok(false);
+        }
+        reader_a.consume(len);
+        reader_b.consume(len);
+    }
+}
+
+/// Generate a unified diff of the contents of two text files.
+///
+/// Return an empty string if the two files are identical.
+#[cfg(feature = "diff")]
+pub fn diff_files(a: &str, b: &str) -> Result<String> {
+    let content_a = read_file_to_string(a)?;
+    let content_b = read_file_to_string(b)?;
+    if content_a == content_b {
+        return Ok("".to_string());
+    }
+    let diff = similar::TextDiff::from_lines(&content_a, &content_b);
+    let res = diff.unified_diff().header(a, b).to_string();
+    Ok(res)
+}
+
+/// Create a directory and all of its missing parent directories.
+///
+/// Do nothing if the directory already exists, and return an error if the path
+/// exists but is not a directory.
+pub fn create_dir_all(path: &str) -> Result<()> {
+    if is_directory(path) {
+        return Ok(());
+    } else if file_exists(path) {
+        bail!("Failed to create directory: {}: not a directory", path)
+    } else if debug::get_dry_run() {
+        info!("Dry run: create directory: {}", path);
+        return Ok(());
+    }
+
+    match fs::create_dir_all(path) {
+        Ok(_) => Ok(()),
+        Err(err) => bail!("Failed to create directory: {}: {}", path, err),
+    }
+}
+
+/// Create the parent directory of a file if it does not exist.
+fn create_parent_directory(filename: &str) -> Result<()> {
+    match get_parent_directory(filename) {
+        Some(dir) => create_dir_all(&dir),
+        None => Ok(()),
+    }
+}
+
+/// Copy a file to a destination, creating its parent directory if missing.
+///
+/// Return the number of copied bytes, which is `0` in the dry-run mode.
+pub fn copy_file(src: &str, dst: &str) -> Result<u64> {
+    if debug::get_dry_run() {
+        info!("Dry run: copy file: {} to {}", src, dst);
+        return Ok(0);
+    }
+    create_parent_directory(dst)?;
+    match fs::copy(src, dst) {
+        Ok(size) => Ok(size),
+        Err(err) => bail!("Failed to copy file: {} to {}: {}", src, dst, err),
+    }
+}
+
+/// Move a file to a destination, creating its parent directory if missing.
+///
+/// Fall back to copying and then deleting the source file when it cannot be
+/// renamed, e.g., when moving across file systems.
+pub fn move_file(src: &str, dst: &str) -> Result<()> {
+    if debug::get_dry_run() {
+        info!("Dry run: move file: {} to {}", src, dst);
+        return Ok(());
+    }
+    create_parent_directory(dst)?;
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    copy_file(src, dst)?;
+    match fs::remove_file(src) {
+        Ok(_) => Ok(()),
+        Err(err) => bail!("Failed to move file: {} to {}: {}", src, dst, err),
+    }
+}
+
+/// Remove a file, doing nothing if it does not exist.
+pub fn remove_file(path: &str) -> Result<()> {
+    if debug::get_dry_run() {
+        info!("Dry run: remove file: {}", path);
+        return Ok(());
+    }
+    match fs::remove_file(path) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => bail!("Failed to remove file: {}: {}", path, err),
+    }
+}
+
+/// Remove a directory and all of its content, doing nothing if it does not
+/// exist.
+pub fn remove_dir_all(path: &str) -> Result<()> {
+    if debug::get_dry_run() {
+        info!("Dry run: remove directory: {}", path);
+        return Ok(());
+    }
+    match fs::remove_dir_all(path) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => bail!("Failed to remove directory: {}: {}", path, err),
+    }
+}
+
+/// Write content to a file atomically.
+///
+/// The content is first written to a temporary file in the same directory,
+/// which is then renamed to the output file, so that readers never observe a
+/// partially written file.
+pub fn write_atomic(path: &str, content: &[u8]) -> Result<()> {
+    if debug::get_dry_run() {
+        info!("Dry run: write {} bytes to file: {}", content.len(), path);
+        return Ok(());
+    }
+    let output_dir = get_parent_directory(path).unwrap_or_else(|| ".".into());
+    create_dir_all(&output_dir)?;
+    let mut temp_file = match NamedTempFile::new_in(&output_dir) {
+        Ok(file) => file,
+        Err(err) => {
+            bail!("Failed to create temporary file for: {}: {}", path, err)
+        }
+    };
+    if let Err(err) = temp_file.write_all(content) {
+        bail!("Failed to write file: {}: {}", path, err)
+    }
+    match temp_file.persist(path) {
+        Ok(_) => Ok(()),
+        Err(err) => bail!("Failed to write file: {}: {}", path, err),
+    }
+}
+
+/// Read a file line by line, without loading the whole file into memory.
+///
+/// Each line is yielded as a separate result, which can individually fail,
+/// e.g., when the line is not valid UTF-8.
+pub fn read_lines(path: &str) -> Result<impl Iterator<Item = Result<String>>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => bail!("Failed to open file: {}: {}", path, err),
+    };
+    let path = path.to_owned();
+    let lines = BufReader::new(file).lines().map(move |line| {
+        line.map_err(|err| anyhow!("Failed to read line: {}: {}", path, err))
+    });
+    Ok(lines)
+}
+
+/// Count the number of lines of a file, without loading the whole file into
+/// memory.
+///
+/// The last line is counted even if it does not end with a newline.
+pub fn count_lines(path: &str) -> Result<usize> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => bail!("Failed to open file: {}: {}", path, err),
+    };
+    let mut reader = BufReader::new(file);
+    let mut num_lines = 0;
+    let mut last_byte = None;
+    loop {
+        let buffer = match reader.fill_buf() {
+            Ok(buffer) => buffer,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => bail!("Failed to read file: {}: {}", path, err),
+        };
+        if buffer.is_empty() {
+            break;
+        }
+        num_lines += buffer.iter().filter(|&&c| c == b'\n').count();
+        last_byte = buffer.last().copied();
+        let len = buffer.len();
+        reader.consume(len);
+    }
+    match last_byte {
+        Some(c) if c != b'\n' => Ok(num_lines + 1),
+        _ => Ok(num_lines),
+    }
+}
+
+/// Append a string to a file, creating the file and its parent directory if
+/// they do not exist.
+pub fn append_to_file(path: &str, content: &str) -> Result<()> {
+    if debug::get_dry_run() {
+        info!("Dry run: append {} bytes to file: {}", content.len(), path);
+        return Ok(());
+    }
+    create_parent_directory(path)?;
+    let mut options = OpenOptions::new();
+    let mut file = match options.create(true).append(true).open(path) {
+        Ok(file) => file,
+        Err(err) => bail!("Failed to open file: {}: {}", path, err),
+    };
+    if let Err(err) = file.write_all(content.as_bytes()) {
+        bail!("Failed to append to file: {}: {}", path, err)
+    }
+    match file.flush() {
+        Ok(_) => Ok(()),
+        Err(err) => bail!("Failed to append to file: {}: {}", path, err),
+    }
+}
+
+/// Find all paths matching a glob pattern, such as `src/**/*.sol`.
+///
+/// Return the matching paths in sorted order, or an empty list if no path
+/// matches.
+#[cfg(feature = "glob")]
+pub fn glob(pattern: &str) -> Result<Vec<String>> {
+    let paths = match ::glob::glob(pattern) {
+        Ok(paths) => paths,
+        Err(err) => bail!("Invalid glob pattern: {}: {}", pattern, err),
+    };
+    let mut res = vec![];
+    for path in paths {
+        match path {
+            Ok(path) => res.push(path.display().to_string()),
+            Err(err) => bail!("Failed to match pattern: {}: {}", pattern, err),
+        }
+    }
+    res.sort();
+    Ok(res)
+}
+
+/// Check if a file name has one of the given extensions, ignoring case.
+fn has_any_extension(filename: &str, exts: &[&str]) -> bool {
+    match get_file_ext(filename) {
+        Some(ext) => exts.iter().any(|e| e.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+/// List all files of a directory having one of the given extensions, ignoring
+/// case.
+pub fn ls_dir_with_ext(dir_path: &str, exts: &[&str]) -> Vec<String> {
+    system::ls_dir(dir_path)
+        .into_iter()
+        .filter(|path| is_regular_file(path) && has_any_extension(path, exts))
+        .collect()
+}
+
+/// List all files of a directory and its sub-directories having one of the
+/// given extensions, ignoring case.
+///
+/// See `system::ls_dir_recursive` for the meaning of other parameters.
+pub fn ls_dir_recursive_with_ext(
+    dir_path: &str,
+    exts: &[&str],
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+) -> Vec<String> {
+    system::ls_dir_recursive(dir_path, max_depth, follow_symlinks)
+        .into_iter()
+        .filter(|path| has_any_extension(path, exts))
+        .collect()
+}
+
+/// Get the absolute path of a path, with all symbolic links resolved.
+///
+/// Return an error if the path does not exist.
+pub fn canonicalize(path: &str) -> Result<String> {
+    match fs::canonicalize(path) {
+        Ok(path) => Ok(path.display().to_string()),
+        Err(err) => bail!("Failed to canonicalize path: {}: {}", path, err),
+    }
+}
+
+/// Get the absolute path of a path, with all symbolic links resolved.
+///
+/// Fall back to removing `.` and `..` components lexically if the path does
+/// not exist.
+pub fn canonicalize_lossy(path: &str) -> String {
+    if let Ok(path) = canonicalize(path) {
+        return path;
+    }
+    let path = match std::env::current_dir() {
+        Ok(dir) => dir.join(path),
+        Err(_) => PathBuf::from(path),
+    };
+    normalize_components(&path).display().to_string()
+}
+
+/// Normalize a path lexically, without accessing the file system.
+///
+/// Remove `.` components, duplicate separators, and `..` components following
+/// a normal component, and use the separator of the current platform. Leading
+/// `..` components of a relative path are kept, and `..` components right
+/// after the root are dropped.
+pub fn normalize_path(path: &str) -> String {
+    let res = normalize_components(Path::new(path));
+    match res.as_os_str().is_empty() {
+        true => ".".to_owned(),
+        false => res.display().to_string(),
+    }
+}
+
+/// Remove `.` and `..` components of a path lexically.
+fn normalize_components(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match res.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    res.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => res.push(component),
+            },
+            _ => res.push(component),
+        }
+    }
+    res
+}
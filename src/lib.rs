@@ -26,11 +26,14 @@ pub mod macros;
 
 // Export sub modules
 // pub mod cli;
+pub mod anyhow;
 pub mod arg;
 pub mod debug;
+pub mod encoding;
 pub mod file;
 pub mod naming;
 pub mod print;
 pub mod report;
 pub mod string;
 pub mod system;
+pub mod traits;
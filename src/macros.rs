@@ -26,230 +26,208 @@ macro_rules! function {
 }
 
 /// Macro to format a string with indentation for each line.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! formati {
-    ($indent:expr, $($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            let tw = report::get_terminal_width() -
-                $crate::debug::DEBUG_MARKER_LEN - 3;
-            let mut msg = String::new();
-            let _ = write!(msg, $($arg)*);
-            report::beautify_string("", false, $indent, "", &msg, tw)
-        }
-    }
+    ($indent:expr, $($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        let tw = report::get_terminal_width() -
+            $crate::debug::get_debug_marker_len() - 3;
+        let mut msg = String::new();
+        let _ = write!(msg, $($arg)*);
+        report::beautify_string("", false, $indent, "", &msg, tw)
+    }}
 }
 
 /// Macro to format a string with a prefix message for console printing.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! formatp {
-    ($lindent:expr, $rindent:expr, $prefix:expr, $($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            let tw = report::get_terminal_width() -
-                $crate::debug::DEBUG_MARKER_LEN - 3 - $rindent;
-            let mut msg = String::new();
-            let _ = write!(msg, $($arg)*);
-            report::beautify_string("", false, $lindent, $prefix, &msg, tw)
-        }
-    }
+    ($lindent:expr, $rindent:expr, $prefix:expr, $($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        let tw = report::get_terminal_width() -
+            $crate::debug::get_debug_marker_len() - 3 - $rindent;
+        let mut msg = String::new();
+        let _ = write!(msg, $($arg)*);
+        report::beautify_string("", false, $lindent, $prefix, &msg, tw)
+    }}
 }
 
 /// Override the default print! macro to disable printing when needed.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! print {
-    () => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                std::print!("")
-            }
+    () => {{
+        if !$crate::debug::get_disable_printing() {
+            std::print!("")
         }
-    };
-    ($($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            if !$crate::debug::DISABLE_PRINTING {
-                if $crate::debug::DEBUG_MODE {
-                    let marker = "[inf] ";
-                    $crate::debug::DEBUG_MARKER_LEN = marker.len();
-                    let msg = std::fmt::format(std::format_args!($($arg)*));
-                    let tw = report::get_terminal_width();
-                    let msg = report::beautify_string(marker, false, 0, "", &msg, tw);
-                    std::print!("{}", msg);
-                    $crate::debug::DEBUG_MARKER_LEN = 0
-                }
-                else {
-                    let mut msg = String::new();
-                    let _ = write!(msg, $($arg)*);
-                    std::print!("{}", msg);
-                }
+    }};
+    ($($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        if !$crate::debug::get_disable_printing() {
+            if $crate::debug::get_debug_mode() {
+                let marker = "[inf] ";
+                $crate::debug::set_debug_marker_len(marker.len());
+                let msg = std::fmt::format(std::format_args!($($arg)*));
+                let tw = report::get_terminal_width();
+                let msg = report::beautify_string(marker, false, 0, "", &msg, tw);
+                std::print!("{}", msg);
+                $crate::debug::set_debug_marker_len(0)
+            }
+            else {
+                let mut msg = String::new();
+                let _ = write!(msg, $($arg)*);
+                std::print!("{}", msg);
             }
         }
-    }
+    }}
 }
 
 /// Override the default println! macro to disable printing when needed.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 // #[allow_internal_unstable(format_args_nl)]
 #[macro_export]
 macro_rules! println {
-    () => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                std::println!("")
-            }
+    () => {{
+        if !$crate::debug::get_disable_printing() {
+            std::println!("")
         }
-    };
-    ($($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            if !$crate::debug::DISABLE_PRINTING {
-                if $crate::debug::DEBUG_MODE {
-                    let marker = "[inf] ";
-                    $crate::debug::DEBUG_MARKER_LEN = marker.len();
-                    let msg = std::fmt::format(std::format_args!($($arg)*));
-                    let tw = report::get_terminal_width();
-                    let msg = report::beautify_string(marker, false, 0, "", &msg, tw);
-                    std::println!("{}", msg);
-                    $crate::debug::DEBUG_MARKER_LEN = 0
-                }
-                else {
-                    // std::io::_print($crate::format_args_nl!($($arg)*));
-                    let mut msg = String::new();
-                    let _ = writeln!(msg, $($arg)*);
-                    std::print!("{}", msg);
-                }
+    }};
+    ($($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        if !$crate::debug::get_disable_printing() {
+            if $crate::debug::get_debug_mode() {
+                let marker = "[inf] ";
+                $crate::debug::set_debug_marker_len(marker.len());
+                let msg = std::fmt::format(std::format_args!($($arg)*));
+                let tw = report::get_terminal_width();
+                let msg = report::beautify_string(marker, false, 0, "", &msg, tw);
+                std::println!("{}", msg);
+                $crate::debug::set_debug_marker_len(0)
+            }
+            else {
+                // std::io::_print($crate::format_args_nl!($($arg)*));
+                let mut msg = String::new();
+                let _ = writeln!(msg, $($arg)*);
+                std::print!("{}", msg);
             }
         }
-    }
+    }}
 }
 
 /// Macro to print messages with the H0 separator level (=====).
 #[allow(unused_imports)]
 #[macro_export]
 macro_rules! print_header_0 {
-    ($($arg:tt)*) => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                let mut ruler = "=".repeat(55);
-                if $crate::debug::DEBUG_MODE {
-                    ruler = "[inf] ".to_owned() + &ruler;
-                };
-                std::println!("{}", ruler);
-                $crate::println!($($arg)*);
-            }
+    ($($arg:tt)*) => {{
+        if !$crate::debug::get_disable_printing() {
+            let mut ruler = "=".repeat(55);
+            if $crate::debug::get_debug_mode() {
+                ruler = "[inf] ".to_owned() + &ruler;
+            };
+            std::println!("{}", ruler);
+            $crate::println!($($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print messages with the H1 separator level (-----).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! print_header_1 {
-    ($($arg:tt)*) => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                let mut ruler = "-".repeat(36);
-                if $crate::debug::DEBUG_MODE {
-                    ruler = "[inf] ".to_owned() + &ruler;
-                };
-                std::println!("{}", ruler);
-                $crate::println!($($arg)*);
-            }
+    ($($arg:tt)*) => {{
+        if !$crate::debug::get_disable_printing() {
+            let mut ruler = "-".repeat(36);
+            if $crate::debug::get_debug_mode() {
+                ruler = "[inf] ".to_owned() + &ruler;
+            };
+            std::println!("{}", ruler);
+            $crate::println!($($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print messages with the H2 separator level (-----).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! print_header_2 {
-    ($($arg:tt)*) => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                let mut ruler = "-".repeat(23);
-                if $crate::debug::DEBUG_MODE {
-                    ruler = "[inf] ".to_owned() + &ruler;
-                };
-                std::println!("{}", ruler);
-                $crate::println!($($arg)*);
-            }
+    ($($arg:tt)*) => {{
+        if !$crate::debug::get_disable_printing() {
+            let mut ruler = "-".repeat(23);
+            if $crate::debug::get_debug_mode() {
+                ruler = "[inf] ".to_owned() + &ruler;
+            };
+            std::println!("{}", ruler);
+            $crate::println!($($arg)*);
         }
-    }
+    }}
 }
 
 /// Override the default todo! macro to print todo message and logging location.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 // #[allow_internal_unstable(format_args_nl)]
 #[macro_export]
 macro_rules! todo {
-    () => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            use $crate::debug;
-            if !debug::DISABLE_PRINTING {
-                let marker = if debug::DEBUG_MODE { "[!!!] " } else { "" };
-                debug::DEBUG_MARKER_LEN = marker.len();
-                let msg = "TODO: Not yet implemented!\n";
-                let tw = report::get_terminal_width();
-                let func = std::format!("{}", $crate::function!());
-                let file = std::format!("{}:{}:{}",
-                                        std::file!(), std::line!(),
-                                        std::column!());
-                let msg = "\n".to_owned() +
-                    &report::beautify_string(marker, true, 0, "", &msg, tw) +
-                    "\n" + &report::log_function_name(&func, tw) +
-                    "\n" + &report::log_file_name(&file, tw);
-                std::println!("{}", msg);
-                debug::DEBUG_MARKER_LEN = 0
-            }
-        }
-    };
-    ($($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            use $crate::debug;
-            if !$crate::debug::DISABLE_PRINTING {
-                let marker = if debug::DEBUG_MODE { "[!!!] " } else { "" };
-                debug::DEBUG_MARKER_LEN = marker.len();
-                // let msg = "TODO: ".to_owned() +
-                //     &std::fmt::format(bstd::format_args_nl!($($arg)*));
-                let mut msg = "TODO: ".to_owned();
-                let _ = write!(msg, $($arg)*);
-                let tw = report::get_terminal_width();
-                let func = format!("{}", $crate::function!());
-                let file = format!("{}:{}:{}",
+    () => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        use $crate::debug;
+        if !debug::get_disable_printing() {
+            let marker = if debug::get_debug_mode() { "[!!!] " } else { "" };
+            debug::set_debug_marker_len(marker.len());
+            let msg = "TODO: Not yet implemented!\n";
+            let tw = report::get_terminal_width();
+            let func = std::format!("{}", $crate::function!());
+            let file = std::format!("{}:{}:{}",
                                     std::file!(), std::line!(),
                                     std::column!());
-                let msg = "\n".to_owned() +
-                    &report::beautify_string(marker, false, 0, "", &msg, tw) +
-                    "\n" + &report::log_function_name(&func, tw) +
-                    "\n" + &report::log_file_name(&file, tw);
-                std::println!("{}", msg);
-                debug::DEBUG_MARKER_LEN = 0
-            }
+            let msg = "\n".to_owned() +
+                &report::beautify_string(marker, true, 0, "", &msg, tw) +
+                "\n" + &report::log_function_name(&func, tw) +
+                "\n" + &report::log_file_name(&file, tw);
+            std::println!("{}", msg);
+            debug::set_debug_marker_len(0)
         }
-    }
+    }};
+    ($($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        use $crate::debug;
+        if !$crate::debug::get_disable_printing() {
+            let marker = if debug::get_debug_mode() { "[!!!] " } else { "" };
+            debug::set_debug_marker_len(marker.len());
+            // let msg = "TODO: ".to_owned() +
+            //     &std::fmt::format(bstd::format_args_nl!($($arg)*));
+            let mut msg = "TODO: ".to_owned();
+            let _ = write!(msg, $($arg)*);
+            let tw = report::get_terminal_width();
+            let func = format!("{}", $crate::function!());
+            let file = format!("{}:{}:{}",
+                                std::file!(), std::line!(),
+                                std::column!());
+            let msg = "\n".to_owned() +
+                &report::beautify_string(marker, false, 0, "", &msg, tw) +
+                "\n" + &report::log_function_name(&func, tw) +
+                "\n" + &report::log_file_name(&file, tw);
+            std::println!("{}", msg);
+            debug::set_debug_marker_len(0)
+        }
+    }}
 }
 
 /// Core macro to print a debugging message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debug_core {
     ($marker:expr, $indent:expr, $prefix:expr, $($arg:tt)*) => {
         // unsafe {
             use $crate::report;
             use std::fmt::Write as FmtWrite;
-            $crate::debug::DEBUG_MARKER_LEN = $marker.len();
+            $crate::debug::set_debug_marker_len($marker.len());
             // let msg = std::fmt::format(std::format_args_nl!($($arg)*));
             let mut msg = String::new();
             let _ = write!(msg, $($arg)*);
@@ -257,289 +235,292 @@ macro_rules! debug_core {
             let msg = report::beautify_string($marker, false, $indent, $prefix,
                                               &msg, tw);
             std::println!("{}", msg);
-            $crate::debug::DEBUG_MARKER_LEN = 0
+            $crate::debug::set_debug_marker_len(0)
         // }
     }
 }
 
+/// Macro to print an information message, unless printing is disabled.
+#[allow(unused_imports)]
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if !debug::get_disable_printing() {
+            $crate::debug_core!("[inf] ", 0, "", $($arg)*);
+        }
+    }}
+}
+
 /// Macro to print a debugging message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debug {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbg] ", 0, "", $($arg)*);
-            }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbg] ", 0, "", $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print a deep-debugging message.
 #[macro_export]
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 macro_rules! ddebug {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbx] ", 0, "", $($arg)*);
-            }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbx] ", 0, "", $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print a debugging message with indented lines.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debugi {
-    ($indent:expr, $($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbg] ", $indent, "", $($arg)*);
-            }
+    ($indent:expr, $($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbg] ", $indent, "", $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print a deep-debugging message with indented lines.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! ddebugi {
-    ($indent:expr, $($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbx] ", $indent, "", $($arg)*);
-            }
+    ($indent:expr, $($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbx] ", $indent, "", $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print a debugging message which is indented with a prefix message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debugp {
-    ($indent:expr, $prefix:expr, $($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbg] ", $indent, $prefix, $($arg)*);
-            }
+    ($indent:expr, $prefix:expr, $($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbg] ", $indent, $prefix, $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print a deep-debugging message which is indented with a prefix
 /// message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! ddebugp {
-    ($indent:expr, $prefix:expr, $($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                $crate::debug_core!("[dbx] ", $indent, $prefix, $($arg)*);
-            }
+    ($indent:expr, $prefix:expr, $($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            $crate::debug_core!("[dbx] ", $indent, $prefix, $($arg)*);
         }
-    }
+    }}
 }
 
 /// Macro to print debugging messages with the H0 separator level (=====).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debug_header_0 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEBUG_MODE && !debug::DISABLE_PRINTING {
-                let ruler = &"=".repeat(55);
-                let ruler = "[dbg] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::debug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_debug_mode() && !debug::get_disable_printing() {
+            let ruler = &"=".repeat(55);
+            let ruler = "[dbg] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::debug!($($arg)*);
+            std::print!("");
+        }
+    }}
 }
 
 /// Macro to print deep-debugging messages with the H0 separator level (=====).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! ddebug_header_0 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                let ruler = &"=".repeat(55);
-                let ruler = "[dbx] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::ddebug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            let ruler = &"=".repeat(55);
+            let ruler = "[dbx] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::ddebug!($($arg)*);
+            std::print!("");
+        }
+    }}
 }
 
 /// Macro to print debugging messages with the H1 separator level (-----).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debug_header_1 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEBUG_MODE && !debug::DISABLE_PRINTING {
-                let ruler = &"-".repeat(36);
-                let ruler = "[dbg] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::debug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_debug_mode() && !debug::get_disable_printing() {
+            let ruler = &"-".repeat(36);
+            let ruler = "[dbg] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::debug!($($arg)*);
+            std::print!("");
+        }
+    }}
 }
 
 /// Macro to print deep-debugging messages with the H1 separator level (-----).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! ddebug_header_1 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                let ruler = &"-".repeat(36);
-                let ruler = "[dbx] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::ddebug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            let ruler = &"-".repeat(36);
+            let ruler = "[dbx] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::ddebug!($($arg)*);
+            std::print!("");
+        }
+    }}
 }
 
 /// Macro to print debugging messages with the H2 separator level (---).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! debug_header_2 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::report;
-            if $crate::debug::DEBUG_MODE && !$crate::debug::DISABLE_PRINTING {
-                let ruler = &"-".repeat(23);
-                let ruler = "[dbg] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::debug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::report;
+        if $crate::debug::get_debug_mode() && !$crate::debug::get_disable_printing() {
+            let ruler = &"-".repeat(23);
+            let ruler = "[dbg] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::debug!($($arg)*);
+            std::print!("");
+        }
+    }}
 }
 
 /// Macro to print deep-debugging messages with the H2 separator level (---).
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! ddebug_header_2 {
-    ($($arg:tt)*) => {
-        unsafe {
-            use $crate::debug;
-            if debug::DEEP_DEBUG_MODE && !debug::DISABLE_PRINTING {
-                let ruler = &"-".repeat(23);
-                let ruler = "[dbx] ".to_owned() + ruler;
-                std::println!("{}", ruler);
-                $crate::ddebug!($($arg)*);
-                std::print!("");
-            }
-        }
-    }
+    ($($arg:tt)*) => {{
+        use $crate::debug;
+        if debug::get_deep_debug_mode() && !debug::get_disable_printing() {
+            let ruler = &"-".repeat(23);
+            let ruler = "[dbx] ".to_owned() + ruler;
+            std::println!("{}", ruler);
+            $crate::ddebug!($($arg)*);
+            std::print!("");
+        }
+    }}
+}
+
+/// Macro to run a block and print its elapsed time as a debugging message.
+///
+/// The elapsed time is printed by `debug!`, so nothing is printed unless the
+/// debug mode is enabled. Return the value of the block.
+#[macro_export]
+macro_rules! time_it {
+    ($label:expr, $body:expr) => {{
+        let start = std::time::Instant::now();
+        let res = $body;
+        let elapsed = $crate::print::format_duration(start.elapsed());
+        $crate::debug!("{}: {}", $label, elapsed);
+        res
+    }};
 }
 
 /// Macro to print a fixme message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! fixme {
-    () => {
-        unsafe {
-            if $crate::debug::DEBUG_MODE && !$crate::debug::DISABLE_PRINTING {
-                std::print!("\n")
-            }
+    () => {{
+        if $crate::debug::get_debug_mode() && !$crate::debug::get_disable_printing() {
+            std::print!("\n")
         }
-    };
-    ($($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            use $crate::debug;
-            if !debug::DISABLE_PRINTING {
-                let marker = if debug::DEBUG_MODE { "[!!!]" } else { "" };
-                debug::DEBUG_MARKER_LEN = marker.len();
-                let mut msg = "FIXME: ".to_owned();
-                let _ = write!(msg, $($arg)*);
-                let tw = report::get_terminal_width();
-                let func = std::format!("{}", $crate::function!());
-                let file = std::format!("{}:{}:{}",
-                                        std::file!(), std::line!(),
-                                        std::column!());
-                let msg =  "\n".to_owned() +
-                    &report::beautify_string(marker, true, 0, "", &msg, tw) +
-                    "\n" + &report::log_function_name(&func, tw) +
-                    "\n" + &report::log_file_name(&file, tw);
-                std::println!("{}", msg);
-                debug::DEBUG_MARKER_LEN = 0
-            }
+    }};
+    ($($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        use $crate::debug;
+        if !debug::get_disable_printing() {
+            let marker = if debug::get_debug_mode() { "[!!!]" } else { "" };
+            debug::set_debug_marker_len(marker.len());
+            let mut msg = "FIXME: ".to_owned();
+            let _ = write!(msg, $($arg)*);
+            let tw = report::get_terminal_width();
+            let func = std::format!("{}", $crate::function!());
+            let file = std::format!("{}:{}:{}",
+                                    std::file!(), std::line!(),
+                                    std::column!());
+            let msg =  "\n".to_owned() +
+                &report::beautify_string(marker, true, 0, "", &msg, tw) +
+                "\n" + &report::log_function_name(&func, tw) +
+                "\n" + &report::log_file_name(&file, tw);
+            std::println!("{}", msg);
+            debug::set_debug_marker_len(0)
         }
-    }
+    }}
 }
 
 /// Macro to print a warning message.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! warning {
-    () => {
-        unsafe {
-            if !$crate::debug::DISABLE_PRINTING {
-                std::print!("\n")
-            }
-        }
-    };
-    ($($arg:tt)*) => {
-        unsafe {
-            use std::fmt::Write as FmtWrite;
-            use $crate::report;
-            use $crate::debug;
-            if !debug::DISABLE_PRINTING {
-                let marker = if debug::DEBUG_MODE { "[WRN]" } else { "" };
-                debug::DEBUG_MARKER_LEN = marker.len();
-                let mut msg = String::new();
-                let _ = write!(msg, $($arg)*);
-                let tw = report::get_terminal_width();
-                let func = std::format!("{}", $crate::function!());
-                let file = std::format!("{}:{}:{}",
-                                        std::file!(), std::line!(),
-                                        std::column!());
-                let msg =  "\n".to_owned() +
-                    &report::beautify_string(marker, true, 0, "", &msg, tw) +
-                    "\n" + &report::log_function_name(&func, tw) +
-                    "\n" + &report::log_file_name(&file, tw);
-                std::println!("{}", msg);
-                debug::DEBUG_MARKER_LEN = 0
-            }
+    () => {{
+        if !$crate::debug::get_disable_printing() {
+            std::print!("\n")
         }
-    }
+    }};
+    ($($arg:tt)*) => {{
+        use std::fmt::Write as FmtWrite;
+        use $crate::report;
+        use $crate::debug;
+        if !debug::get_disable_printing() {
+            let marker = if debug::get_debug_mode() { "[WRN]" } else { "" };
+            debug::set_debug_marker_len(marker.len());
+            let mut msg = String::new();
+            let _ = write!(msg, $($arg)*);
+            let tw = report::get_terminal_width();
+            let func = std::format!("{}", $crate::function!());
+            let file = std::format!("{}:{}:{}",
+                                    std::file!(), std::line!(),
+                                    std::column!());
+            let msg =  "\n".to_owned() +
+                &report::beautify_string(marker, true, 0, "", &msg, tw) +
+                "\n" + &report::log_function_name(&func, tw) +
+                "\n" + &report::log_file_name(&file, tw);
+            std::println!("{}", report::colorize(&msg, report::ANSI_YELLOW));
+            debug::set_debug_marker_len(0)
+        }
+    }}
 }
 
-/// Macro to print an error and exit the program.
+/// Macro to print an error to stderr and exit the program.
+///
+/// The error is printed even when printing is disabled.
 #[allow(unused_imports)]
 #[macro_export]
 macro_rules! error {
     ($($arg:tt)*) => ({
-        std::println!($($arg)*);
+        let msg = std::fmt::format(std::format_args!($($arg)*));
+        let msg = $crate::report::colorize(&msg, $crate::report::ANSI_RED);
+        std::eprintln!("{}", msg);
         std::process::exit(1)
     })
 }
 
-/// Short-hand expression for it-then-else.
+/// Short-hand expression for if-then-else.
+///
+/// `ite!(test, true_expr, false_expr)` expands to
+/// `if test { true_expr } else { false_expr }`, so only the expression of the
+/// taken branch is evaluated, and side effects of the other branch never run.
 #[macro_export]
 macro_rules! ite {
     ($test:expr, $true_expr:expr, $false_expr:expr) => {
@@ -594,39 +575,35 @@ macro_rules! get_backtrace {
 /// Macro to return an `anyhow` error and backtrace.
 ///
 /// Need to declare `backtrace` crate dependency in Cargo.ml to use this macro.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! anyhow_backtrace {
-    ($($arg:tt)*) => {
-        unsafe {
-            let err = anyhow::anyhow!($($arg)*);
-            let backtrace = match rutil::debug::DEEP_DEBUG_MODE {
-                true => format!("\n--------------------------\n{}\n{:?}",
-                                "Backtrace:",
-                                backtrace::Backtrace::new()),
-                false => "".to_owned()
-            };
-            anyhow::anyhow!(format!("{}{}", err, backtrace))
-        }
-    };
+    ($($arg:tt)*) => {{
+        let err = anyhow::anyhow!($($arg)*);
+        let backtrace = match $crate::debug::get_deep_debug_mode() {
+            true => format!("\n--------------------------\n{}\n{:?}",
+                            "Backtrace:",
+                            backtrace::Backtrace::new()),
+            false => "".to_owned()
+        };
+        anyhow::anyhow!(format!("{}{}", err, backtrace))
+    }};
 }
 
 /// Macro to report an `anyhow` error and backtrace.
 ///
 /// Need to declare `backtrace` crate dependency in Cargo.ml to use this macro.
-#[allow(unused_imports, unused_unsafe)]
+#[allow(unused_imports)]
 #[macro_export]
 macro_rules! bail_backtrace {
-    ($($arg:tt)*) => {
-        unsafe {
-            let err = anyhow::anyhow!($($arg)*);
-            let backtrace = match rutil::debug::DEEP_DEBUG_MODE {
-                true => format!("\n--------------------------\n{}\n{:?}",
-                                "Backtrace:",
-                                backtrace::Backtrace::new()),
-                false => "".to_owned()
-            };
-            anyhow::bail!(format!("{}{}", err, backtrace))
-        }
-    };
+    ($($arg:tt)*) => {{
+        let err = anyhow::anyhow!($($arg)*);
+        let backtrace = match $crate::debug::get_deep_debug_mode() {
+            true => format!("\n--------------------------\n{}\n{:?}",
+                            "Backtrace:",
+                            backtrace::Backtrace::new()),
+            false => "".to_owned()
+        };
+        anyhow::bail!(format!("{}{}", err, backtrace))
+    }};
 }
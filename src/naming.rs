@@ -1,16 +1,24 @@
 //! Module to provide naming utilities.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Separator between a base name and its index in a fresh name.
+pub const NAME_INDEX_SEPARATOR: &str = "_";
 
 /// Data structure capturing a naming environment, which maps names in String to
 /// their indices.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NamingEnv {
     /// Mapping a name to its index in the current scope.
     pub current_naming_index: HashMap<String, usize>,
 
     /// Mapping a name to its index counter (the maximum index of this name).
     pub naming_index_counter: HashMap<String, usize>,
+
+    /// Whether names differing only in case are considered the same name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub case_insensitive: bool,
 }
 
 impl NamingEnv {
@@ -19,45 +27,199 @@ impl NamingEnv {
         NamingEnv {
             current_naming_index: HashMap::new(),
             naming_index_counter: HashMap::new(),
+            case_insensitive: false,
+        }
+    }
+
+    /// Constructor of an environment where names differing only in case are
+    /// considered the same name.
+    ///
+    /// Names are indexed in lowercase, but fresh names keep their casing.
+    pub fn new_case_insensitive() -> Self {
+        NamingEnv {
+            case_insensitive: true,
+            ..NamingEnv::new()
+        }
+    }
+
+    /// Get the key of a name in the index maps.
+    fn name_key(&self, name: &str) -> String {
+        match self.case_insensitive {
+            true => name.to_lowercase(),
+            false => name.to_owned(),
         }
     }
 
     /// Find the current index of a name.
     pub fn get_current_index(&self, name: &str) -> Option<usize> {
-        match self.current_naming_index.get(name) {
+        match self.current_naming_index.get(&self.name_key(name)) {
             None => None,
             Some(0) => None, // by default, consider 0 index as None
             Some(idx) => Some(*idx),
         }
     }
 
+    /// Check if a name has been indexed in the environment.
+    pub fn contains(&self, name: &str) -> bool {
+        self.naming_index_counter.contains_key(&self.name_key(name))
+    }
+
+    /// Find the index counter (the maximum index) of a name.
+    pub fn counter_of(&self, name: &str) -> Option<usize> {
+        self.naming_index_counter.get(&self.name_key(name)).copied()
+    }
+
+    /// Get all names of the current scope and their current indices, sorted
+    /// by names.
+    pub fn all_names(&self) -> Vec<(String, usize)> {
+        let mut names: Vec<(String, usize)> = self
+            .current_naming_index
+            .iter()
+            .map(|(name, idx)| (name.to_owned(), *idx))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Get the number of names of the current scope.
+    pub fn len(&self) -> usize {
+        self.current_naming_index.len()
+    }
+
+    /// Check if the current scope has no name.
+    pub fn is_empty(&self) -> bool {
+        self.current_naming_index.is_empty()
+    }
+
+    /// Clear all names of the environment.
+    pub fn reset(&mut self) {
+        self.current_naming_index.clear();
+        self.naming_index_counter.clear();
+    }
+
     /// Create a new index for a name.
     pub fn create_new_name_index(
         &self,
-        name: &String,
+        name: &str,
     ) -> (Option<usize>, NamingEnv) {
-        // Create the new environment
         let mut new_env = self.to_owned();
+        let idx = new_env.create_new_name_index_mut(name);
+        (idx, new_env)
+    }
+
+    /// Create a new index for a name, updating the environment in place.
+    pub fn create_new_name_index_mut(&mut self, name: &str) -> Option<usize> {
+        let key = self.name_key(name);
 
         // New index
-        let new_idx = match self.naming_index_counter.get(name) {
+        let new_idx = match self.naming_index_counter.get(&key) {
             None => 0,
             Some(idx) => *idx + 1,
         };
 
         // Update current index
-        new_env
-            .current_naming_index
-            .insert(name.to_owned(), new_idx);
+        self.current_naming_index.insert(key.clone(), new_idx);
 
         // Update index counter
-        new_env
-            .naming_index_counter
-            .insert(name.to_owned(), new_idx);
+        self.naming_index_counter.insert(key, new_idx);
 
         // Fine-tune and return result
-        let final_idx = ite!(new_idx == 0, None, Some(new_idx));
-        (final_idx, new_env)
+        ite!(new_idx == 0, None, Some(new_idx))
+    }
+
+    /// Reserve names which must not be used as fresh names.
+    ///
+    /// A reserved name is indexed as used, so that the first fresh name created
+    /// from it is `name_1`.
+    pub fn register_reserved(&self, names: &[&str]) -> NamingEnv {
+        let mut new_env = self.to_owned();
+        for name in names {
+            new_env
+                .naming_index_counter
+                .entry(self.name_key(name))
+                .or_insert(0);
+        }
+        new_env
+    }
+
+    /// Create a fresh name from a base name.
+    ///
+    /// The fresh name is `base` for the first time, then `base_1`, `base_2`,
+    /// ..., skipping names which were already indexed, e.g., `base_1` created
+    /// from the base name `base_1` itself. Each fresh name is also indexed as
+    /// used, so that it is never returned again.
+    pub fn fresh_name(&self, base: &str) -> (String, NamingEnv) {
+        let mut new_env = self.to_owned();
+        let name = new_env.fresh_name_mut(base);
+        (name, new_env)
+    }
+
+    /// Create a fresh name from a base name, like `fresh_name`, but update the
+    /// environment in place.
+    pub fn fresh_name_mut(&mut self, base: &str) -> String {
+        loop {
+            let idx = self.create_new_name_index_mut(base);
+            let name = match idx {
+                None => base.to_owned(),
+                Some(idx) => format!("{}{}{}", base, NAME_INDEX_SEPARATOR, idx),
+            };
+            let key = self.name_key(&name);
+            if idx.is_none() || !self.naming_index_counter.contains_key(&key) {
+                self.naming_index_counter.entry(key).or_insert(0);
+                return name;
+            }
+        }
+    }
+
+    /// Create the environment of a new nested scope.
+    ///
+    /// The current environment should be kept to be restored by `exit_scope`.
+    pub fn enter_scope(&self) -> NamingEnv {
+        self.to_owned()
+    }
+
+    /// Exit the current scope and restore the current indices of the `saved`
+    /// environment when entering this scope.
+    ///
+    /// Index counters are not restored, so that names created in the exited
+    /// scope remain unique.
+    pub fn exit_scope(&self, saved: &NamingEnv) -> NamingEnv {
+        let mut new_env = self.to_owned();
+        new_env.current_naming_index = saved.current_naming_index.clone();
+        new_env
+    }
+
+    /// Merge two environments, e.g., of linked modules, so that fresh names
+    /// created from the merged environment do not clash with names of either
+    /// environment.
+    ///
+    /// The index counter and the current index of each name are the maximum
+    /// of the two environments. Names which were already created in both
+    /// environments, such as `x_1`, are not renamed and thus may still
+    /// collide; only names created after merging are guaranteed to be unique.
+    ///
+    /// The merged environment keeps the case sensitivity of `self`.
+    pub fn merge(&self, other: &NamingEnv) -> NamingEnv {
+        let mut new_env = self.to_owned();
+
+        // Take the maximum index of each name
+        let merge_indices =
+            |indices: &mut HashMap<String, usize>,
+             other_indices: &HashMap<String, usize>| {
+                for (name, other_idx) in other_indices {
+                    let idx = indices.entry(self.name_key(name)).or_insert(0);
+                    *idx = (*idx).max(*other_idx);
+                }
+            };
+        merge_indices(
+            &mut new_env.current_naming_index,
+            &other.current_naming_index,
+        );
+        merge_indices(
+            &mut new_env.naming_index_counter,
+            &other.naming_index_counter,
+        );
+        new_env
     }
 }
 
@@ -66,3 +228,40 @@ impl Default for NamingEnv {
         Self::new()
     }
 }
+
+/// Naming environment which can be shared between threads.
+///
+/// Unlike `NamingEnv`, which returns a new environment for each new name, this
+/// environment is mutated in place, so that all threads see the same indices.
+/// It thus trades the functional immutability of `NamingEnv` for shared
+/// mutation: a name created by a thread changes the environment of all other
+/// threads, and earlier states of the environment cannot be kept.
+#[derive(Debug, Default)]
+pub struct SharedNamingEnv {
+    /// The underlying naming environment, guarded by a mutex.
+    env: Mutex<NamingEnv>,
+}
+
+impl SharedNamingEnv {
+    /// Constructor.
+    pub fn new() -> Self {
+        SharedNamingEnv {
+            env: Mutex::new(NamingEnv::new()),
+        }
+    }
+
+    /// Create a fresh name from a base name, which is unique among all names
+    /// created by all threads.
+    pub fn fresh_name(&self, base: &str) -> String {
+        let mut env = self.env.lock().unwrap_or_else(|err| err.into_inner());
+        env.fresh_name_mut(base)
+    }
+}
+
+impl From<NamingEnv> for SharedNamingEnv {
+    fn from(env: NamingEnv) -> Self {
+        SharedNamingEnv {
+            env: Mutex::new(env),
+        }
+    }
+}
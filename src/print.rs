@@ -1,6 +1,11 @@
 //! Module to provide printing utilities.
 
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display, time::Duration};
+
+use crate::string::StringExt;
+
+/// Number of spaces of each indentation level.
+pub const INDENT_STEP: usize = 2;
 
 /// Print a vector to string by a default printing function.
 ///
@@ -16,6 +21,38 @@ where
         .join(separator)
 }
 
+/// Print a vector to string by a default printing function, or return a
+/// placeholder if the vector is empty.
+///
+/// Elements are separated by a `separator` string.
+pub fn print_vector_or<T>(
+    items: &[T],
+    separator: &str,
+    empty_placeholder: &str,
+) -> String
+where
+    T: Display,
+{
+    match items.is_empty() {
+        true => empty_placeholder.to_owned(),
+        false => print_vector_to_string(items, separator),
+    }
+}
+
+/// Print a vector to string by a default printing function.
+///
+/// Each element, including the last one, is followed by a `terminator` string.
+pub fn print_vector_terminated<T>(items: &[T], terminator: &str) -> String
+where
+    T: Display,
+{
+    items
+        .iter()
+        .map(|elem| format!("{}{}", elem, terminator))
+        .collect::<Vec<String>>()
+        .concat()
+}
+
 /// Print a vector to string using a printer.
 ///
 /// Elements are separated by a `separator` string.
@@ -23,13 +60,363 @@ pub fn print_vector_by_printer<T>(
     items: &[T],
     printer: &dyn Fn(&T) -> String,
     separator: &str,
-) -> String
+) -> String {
+    items
+        .iter()
+        .map(printer)
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+/// Print an optional value to string, or return `none_text` if it is `None`.
+///
+/// This can be used as a printer of `print_vector_by_printer`, e.g., to print
+/// a vector of optional values.
+pub fn print_option<T>(opt: &Option<T>, none_text: &str) -> String
 where
     T: Display,
 {
+    match opt {
+        Some(value) => format!("{}", value),
+        None => none_text.to_owned(),
+    }
+}
+
+/// Print a result to string, where an error is printed as `Error: <error>`.
+pub fn print_result<T, E>(res: &Result<T, E>) -> String
+where
+    T: Display,
+    E: Display,
+{
+    match res {
+        Ok(value) => format!("{}", value),
+        Err(err) => format!("Error: {}", err),
+    }
+}
+
+/// Format a size in bytes to a human-readable string in binary units, such as
+/// `512 B`, `1.5 KiB`, or `3.2 MiB`.
+pub fn format_byte_size(bytes: u64) -> String {
+    let units = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    // Choose the unit by the rounded size, so that it is never `1024.0`.
+    let round = |size: f64| (size * 10.0).round() / 10.0;
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while round(size) >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, units[unit])
+}
+
+/// Print a vector to string using a formatting closure.
+///
+/// Elements are separated by a `separator` string.
+pub fn print_vector_with<T, F>(items: &[T], separator: &str, f: F) -> String
+where
+    F: Fn(&T) -> String,
+{
+    items.iter().map(f).collect::<Vec<String>>().join(separator)
+}
+
+/// Indent all non-empty lines of a string by a number of spaces.
+///
+/// A trailing newline of the string is kept.
+pub fn indent_lines(text: &str, spaces: usize) -> String {
+    let res = text.to_owned().indent(spaces);
+    match text.ends_with('\n') {
+        true => res + "\n",
+        false => res,
+    }
+}
+
+/// Indent all non-empty lines of a string by a number of indentation levels,
+/// each of which is `INDENT_STEP` spaces.
+pub fn indent_block(text: &str, level: usize) -> String {
+    indent_lines(text, level * INDENT_STEP)
+}
+
+/// Print a vector to a numbered list, one element per line.
+///
+/// Elements are numbered from `start`, and numbers are right-aligned.
+pub fn print_vector_numbered<T>(items: &[T], start: usize) -> String
+where
+    T: Display,
+{
+    let last = start + items.len().saturating_sub(1);
+    let width = last.to_string().len();
     items
         .iter()
-        .map(|elem| printer(elem))
+        .enumerate()
+        .map(|(idx, elem)| format!("{:>width$}. {}", start + idx, elem))
         .collect::<Vec<String>>()
-        .join(separator)
+        .join("\n")
+}
+
+/// Format a list in English, such as `a`, `a and b`, or `a, b, and c`, using
+/// the Oxford comma.
+///
+/// Return an empty string if the list is empty.
+pub fn format_list_human<T>(items: &[T]) -> String
+where
+    T: Display,
+{
+    match items {
+        [] => "".to_owned(),
+        [item] => format!("{}", item),
+        [first, second] => format!("{} and {}", first, second),
+        [init @ .., last] => {
+            format!("{}, and {}", print_vector_to_string(init, ", "), last)
+        }
+    }
+}
+
+/// Format a table with aligned columns, separated by two spaces, and a dashed
+/// line below the header.
+///
+/// Rows with fewer cells than the header are padded with empty cells.
+pub fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let num_columns = rows
+        .iter()
+        .map(|row| row.len())
+        .fold(headers.len(), usize::max);
+    let cell = |row: &[String], idx: usize| match row.get(idx) {
+        Some(cell) => cell.to_owned(),
+        None => "".to_owned(),
+    };
+    let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+
+    // Compute the width of each column
+    let widths: Vec<usize> = (0..num_columns)
+        .map(|idx| {
+            rows.iter()
+                .chain(std::iter::once(&headers))
+                .map(|row| cell(row, idx).chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    // Pad cells of a row and join them
+    let format_row = |row: &[String]| {
+        widths
+            .iter()
+            .enumerate()
+            .map(|(idx, width)| format!("{:<width$}", cell(row, idx)))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+
+    let ruler: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    std::iter::once(format_row(&headers))
+        .chain(std::iter::once(ruler.join("  ")))
+        .chain(rows.iter().map(|row| format_row(row)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Format a table in GitHub-flavored Markdown, where pipes in cells are
+/// escaped and line breaks, `\n`, `\r\n`, or `\r`, are replaced by spaces.
+///
+/// Rows with fewer cells than the header are padded with empty cells.
+pub fn to_markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let num_columns = rows
+        .iter()
+        .map(|row| row.len())
+        .fold(headers.len(), usize::max);
+    let format_row = |cells: Vec<&str>| {
+        let cells: Vec<String> = (0..num_columns)
+            .map(|idx| match cells.get(idx) {
+                Some(cell) => cell
+                    .replace('|', "\\|")
+                    .replace("\r\n", " ")
+                    .replace(['\r', '\n'], " "),
+                None => "".to_owned(),
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let ruler = vec!["---"; num_columns];
+    std::iter::once(format_row(headers.to_vec()))
+        .chain(std::iter::once(format_row(ruler)))
+        .chain(rows.iter().map(|row| {
+            format_row(row.iter().map(|cell| cell.as_str()).collect())
+        }))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Format fields to a CSV row as specified by RFC 4180.
+///
+/// Fields containing commas, double quotes, or line breaks are enclosed in
+/// double quotes, where embedded double quotes are doubled.
+pub fn to_csv_row(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| match field.contains(&[',', '"', '\n', '\r'][..]) {
+            true => format!("\"{}\"", field.replace('"', "\"\"")),
+            false => field.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Format rows to CSV as specified by RFC 4180, where each row is terminated
+/// by a CRLF line break.
+pub fn to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            let fields: Vec<&str> = row.iter().map(|f| f.as_str()).collect();
+            to_csv_row(&fields) + "\r\n"
+        })
+        .collect::<Vec<String>>()
+        .concat()
+}
+
+/// Print a map to string, in the iteration order of the map.
+///
+/// Keys and values are separated by `kv_sep`, and entries by `entry_sep`.
+pub fn print_map<K, V>(
+    map: &HashMap<K, V>,
+    kv_sep: &str,
+    entry_sep: &str,
+) -> String
+where
+    K: Display,
+    V: Display,
+{
+    map.iter()
+        .map(|(k, v)| format!("{}{}{}", k, kv_sep, v))
+        .collect::<Vec<String>>()
+        .join(entry_sep)
+}
+
+/// Print a map to string, with entries sorted by keys.
+///
+/// Keys and values are separated by `kv_sep`, and entries by `entry_sep`.
+pub fn print_map_sorted<K, V>(
+    map: &HashMap<K, V>,
+    kv_sep: &str,
+    entry_sep: &str,
+) -> String
+where
+    K: Display + Ord,
+    V: Display,
+{
+    let mut entries: Vec<(&K, &V)> = map.iter().collect();
+    entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+    entries
+        .iter()
+        .map(|(k, v)| format!("{}{}{}", k, kv_sep, v))
+        .collect::<Vec<String>>()
+        .join(entry_sep)
+}
+
+/// Truncate a string to at most `max_chars` characters, ending with `…` when
+/// the string is truncated.
+pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_owned();
+    }
+    match max_chars {
+        0 => "".to_owned(),
+        _ => s.chars().take(max_chars - 1).collect::<String>() + "…",
+    }
+}
+
+/// Format a duration to a compact human-readable string, such as `250µs`,
+/// `350ms`, `1.2s`, or `1h 2m 3s`.
+///
+/// Durations of about one minute or more are rounded down to seconds, and their
+/// zero components are omitted.
+pub fn format_duration(d: Duration) -> String {
+    if d < Duration::from_millis(1) {
+        return format!("{}µs", d.as_micros());
+    } else if d < Duration::from_secs(1) {
+        return format!("{}ms", d.as_millis());
+    } else if d.as_secs_f64() < 59.95 {
+        // Avoid rounding up to `60.0s`.
+        return format!("{:.1}s", d.as_secs_f64());
+    }
+
+    // Round to whole seconds, so that e.g. `59.96s` is printed as `1m`.
+    let secs = d.as_secs_f64().round() as u64;
+    let components =
+        [(secs / 3600, "h"), (secs / 60 % 60, "m"), (secs % 60, "s")];
+    components
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Connectors to draw a tree with Unicode characters: a branch, the last
+/// branch, a vertical line, and a blank, respectively.
+const UNICODE_TREE_CONNECTORS: [&str; 4] = ["├── ", "└── ", "│   ", "    "];
+
+/// Connectors to draw a tree with ASCII characters: a branch, the last branch,
+/// a vertical line, and a blank, respectively.
+const ASCII_TREE_CONNECTORS: [&str; 4] = ["|-- ", "`-- ", "|   ", "    "];
+
+/// Data structure capturing a node of a tree to be rendered.
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    /// Label of the node.
+    pub label: String,
+
+    /// Children of the node.
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Constructor.
+    pub fn new(label: &str, children: Vec<TreeNode>) -> Self {
+        TreeNode {
+            label: label.to_owned(),
+            children,
+        }
+    }
+
+    /// Render the tree rooted at this node, using Unicode connectors.
+    pub fn render(&self) -> String {
+        self.render_with(&UNICODE_TREE_CONNECTORS)
+    }
+
+    /// Render the tree rooted at this node, using ASCII connectors.
+    pub fn render_ascii(&self) -> String {
+        self.render_with(&ASCII_TREE_CONNECTORS)
+    }
+
+    /// Render the tree rooted at this node, using the given connectors.
+    fn render_with(&self, connectors: &[&str; 4]) -> String {
+        /// Render all descendants of a node, whose lines start with `prefix`.
+        fn render_children(
+            node: &TreeNode,
+            prefix: &str,
+            connectors: &[&str; 4],
+            lines: &mut Vec<String>,
+        ) {
+            let [branch, last_branch, vertical, blank] = connectors;
+            let num_children = node.children.len();
+            for (idx, child) in node.children.iter().enumerate() {
+                let is_last = idx + 1 == num_children;
+                let connector = ite!(is_last, last_branch, branch);
+                lines.push(format!("{}{}{}", prefix, connector, child.label));
+                let prefix = prefix.to_owned() + ite!(is_last, blank, vertical);
+                render_children(child, &prefix, connectors, lines);
+            }
+        }
+
+        let mut lines = vec![self.label.clone()];
+        render_children(self, "", connectors, &mut lines);
+        lines.join("\n")
+    }
 }
@@ -6,6 +6,7 @@ use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
 
+use super::debug;
 use super::string::StringExt;
 
 /// Length of a long ruler.
@@ -14,6 +15,24 @@ const LONG_SEPARATOR_LENGTH: usize = 75;
 /// Length of a short ruler.
 const SHORT_SEPARATOR_LENGTH: usize = 55;
 
+/// ANSI escape code of the red color.
+pub const ANSI_RED: &str = "\x1b[31m";
+
+/// ANSI escape code of the yellow color.
+pub const ANSI_YELLOW: &str = "\x1b[33m";
+
+/// ANSI escape code to reset the color.
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+/// Colorize a text by an ANSI color code, if printed messages should be
+/// colorized according to `debug::should_colorize`.
+pub fn colorize(text: &str, color: &str) -> String {
+    match debug::should_colorize() && !text.is_empty() {
+        true => format!("{}{}{}", color, text, ANSI_RESET),
+        false => text.to_owned(),
+    }
+}
+
 /// Printing a reporting message in the format header-separator-body.
 pub fn print_message(header: &str, body: &str) {
     let separator = "-".repeat(cmp::max(header.len(), LONG_SEPARATOR_LENGTH));
@@ -274,7 +293,7 @@ pub fn override_panic_message(note: &'static str) {
             };
 
             write!(info, "| Note: {}", note).ok();
-            std::println!("{}", info)
+            std::println!("{}", colorize(&info, ANSI_RED))
         }));
     }
 }
@@ -1,8 +1,20 @@
 //! Module containing utility functions at the operating system level
 
-use crate::warning;
+use crate::{debug, info, print, warning};
 use anyhow::{bail, Result};
-use std::{env, fs, process};
+use std::{
+    collections::HashSet,
+    env,
+    ffi::OsString,
+    fmt, fs,
+    io::{self, Read},
+    panic,
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
 
 /// Operating system information
 mod os {
@@ -24,6 +36,17 @@ pub fn is_linux_os() -> bool {
     std::env::consts::OS.eq(os::LINUX)
 }
 
+/// Check if the current operating system is macOS
+pub fn is_macos_os() -> bool {
+    std::env::consts::OS.eq(os::MACOS)
+}
+
+/// Get the name of the current operating system, such as `windows`, `linux`,
+/// or `macos`
+pub fn current_os() -> &'static str {
+    std::env::consts::OS
+}
+
 /// Find full path of a command from the environment
 pub fn path_of_command_from_env(cmd: &str) -> Result<String, String> {
     let finder = match env::consts::OS {
@@ -36,29 +59,772 @@ pub fn path_of_command_from_env(cmd: &str) -> Result<String, String> {
     };
 
     match process::Command::new(finder).args([cmd]).output() {
-        Ok(output) => {
+        Ok(output) if output.status.success() => {
             let cmd_path = String::from_utf8(output.stdout).unwrap_or_default();
-            Ok(cmd_path.trim().to_string())
+            // `where.exe` may print several paths, one per line.
+            match cmd_path.lines().next() {
+                Some(path) if !path.trim().is_empty() => {
+                    Ok(path.trim().to_string())
+                }
+                _ => Err(format!("Command not found: {}", cmd)),
+            }
+        }
+
+        _ => Err(format!("Command not found: {}", cmd)),
+    }
+}
+
+/// Find full path of the first command of a list that is available in the
+/// environment
+pub fn which_any(candidates: &[&str]) -> Option<String> {
+    candidates
+        .iter()
+        .find_map(|cmd| path_of_command_from_env(cmd).ok())
+}
+
+/// Check if a command is available in the environment
+pub fn command_exists(cmd: &str) -> bool {
+    path_of_command_from_env(cmd).is_ok()
+}
+
+/// Escape a command argument so that it can be copied and pasted to a shell.
+///
+/// Arguments are single-quoted for POSIX shells. On Windows, they are
+/// double-quoted following the command line parsing rules of Windows, and
+/// then all `cmd.exe` metacharacters, including `%` and the double quotes, are
+/// escaped by `^`, so that `cmd.exe` passes the quoted argument unchanged.
+pub fn shell_escape(arg: &str) -> String {
+    let is_safe = |c: char| {
+        c.is_ascii_alphanumeric()
+            || "_-+=@:,./".contains(c)
+            || (c == '%' && !is_window_os())
+    };
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        return arg.to_owned();
+    }
+
+    if !is_window_os() {
+        return format!("'{}'", arg.replace('\'', "'\\''"));
+    }
+
+    // Backslashes are literal unless they precede a double quote.
+    let mut quoted = "\"".to_owned();
+    let mut num_backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => num_backslashes += 1,
+            '"' => {
+                quoted += &"\\".repeat(num_backslashes + 1);
+                num_backslashes = 0;
+            }
+            _ => num_backslashes = 0,
+        }
+        quoted.push(c);
+    }
+    quoted += &"\\".repeat(num_backslashes);
+    quoted.push('"');
+
+    // Escape metacharacters of `cmd.exe`.
+    let mut res = String::new();
+    for c in quoted.chars() {
+        if "()%!^\"<>&|".contains(c) {
+            res.push('^');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Escape and join command arguments by spaces.
+pub fn shell_escape_args(args: &[&str]) -> String {
+    args.iter()
+        .map(|arg| shell_escape(arg))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Data structure capturing the output of a finished command.
+#[derive(Clone, Debug)]
+pub struct CommandOutput {
+    /// Exit status code, or `-1` if the command was terminated by a signal.
+    pub status: i32,
+
+    /// Captured standard output.
+    pub stdout: String,
+
+    /// Captured standard error.
+    pub stderr: String,
+}
+
+impl From<process::Output> for CommandOutput {
+    fn from(output: process::Output) -> Self {
+        CommandOutput {
+            status: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
         }
+    }
+}
+
+/// Run a command and capture its output.
+///
+/// Return an error only if the command cannot be started. A non-zero exit
+/// status is reported in the output.
+///
+/// In the dry-run mode, the command is only logged and an empty successful
+/// output is returned.
+pub fn run_command(cmd: &str, args: &[&str]) -> Result<CommandOutput> {
+    let mut command = process::Command::new(cmd);
+    command.args(args);
+    capture_command_output(command)
+}
+
+/// Run a command with additional environment variables, which are only set
+/// for the command, and capture its output.
+///
+/// Return an error only if the command cannot be started.
+pub fn run_command_with_env(
+    cmd: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<CommandOutput> {
+    let mut command = process::Command::new(cmd);
+    command.args(args).envs(envs.iter().copied());
+    capture_command_output(command)
+}
+
+/// Run a command in a working directory, which is only set for the command,
+/// and capture its output.
+///
+/// Return an error only if the command cannot be started.
+pub fn run_command_in_dir(
+    cmd: &str,
+    args: &[&str],
+    cwd: &str,
+) -> Result<CommandOutput> {
+    let mut command = process::Command::new(cmd);
+    command.args(args).current_dir(cwd);
+    capture_command_output(command)
+}
+
+/// Log a command instead of running it in the dry-run mode, and return an
+/// empty successful output.
+fn dry_run_command_output(cmd: &str, args: &[&str]) -> CommandOutput {
+    info!("Dry run: {} {}", shell_escape(cmd), shell_escape_args(args));
+    CommandOutput {
+        status: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+    }
+}
 
-        Err(_) => Err(format!("Command not found: {}", cmd)),
+/// Run a configured command and capture its output.
+fn capture_command_output(command: process::Command) -> Result<CommandOutput> {
+    if debug::get_dry_run() {
+        let cmd = command.get_program().to_string_lossy();
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+        return Ok(dry_run_command_output(&cmd, &args));
+    }
+
+    run_query_command(command)
+}
+
+/// Run a configured command and capture its output, even in the dry-run mode.
+///
+/// This is only for read-only commands querying the system, whose output is
+/// needed regardless of the dry-run mode.
+fn run_query_command(mut command: process::Command) -> Result<CommandOutput> {
+    match command.output() {
+        Ok(output) => Ok(CommandOutput::from(output)),
+        Err(err) => {
+            let cmd = command.get_program().to_string_lossy();
+            bail!("Failed to run command: {}: {}", cmd, err)
+        }
     }
 }
 
+/// Errors of running a command, which can be obtained by downcasting the
+/// returned `anyhow::Error`.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The command did not finish within the given time limit.
+    TimedOut(Duration),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::TimedOut(timeout) => {
+                write!(f, "Command timed out after {:?}", timeout)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Interval to poll the status of a running command.
+const COMMAND_POLLING_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Run a command and capture its output, killing it if it does not finish
+/// within `timeout`.
+///
+/// Return `CommandError::TimedOut` when the time limit is exceeded.
+pub fn run_command_with_timeout(
+    cmd: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<CommandOutput> {
+    if debug::get_dry_run() {
+        return Ok(dry_run_command_output(cmd, args));
+    }
+
+    let mut child = match process::Command::new(cmd)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => bail!("Failed to run command: {}: {}", cmd, err),
+    };
+
+    /// Read a pipe to the end in a background thread, so that the command is
+    /// not blocked when the pipe is full.
+    fn read_pipe<R: Read + Send + 'static>(
+        pipe: Option<R>,
+    ) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buffer = vec![];
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_end(&mut buffer).ok();
+            }
+            buffer
+        })
+    }
+
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    child.kill().ok();
+                    child.wait().ok();
+                    return Err(CommandError::TimedOut(timeout).into());
+                }
+                thread::sleep(COMMAND_POLLING_INTERVAL.min(timeout - elapsed))
+            }
+            Err(err) => bail!("Failed to wait for command: {}: {}", cmd, err),
+        }
+    };
+
+    let output = process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    };
+    Ok(CommandOutput::from(output))
+}
+
 /// List all files and sub-directories of a directory
+///
+/// Return an error if the directory or any of its entries cannot be read.
+pub fn ls_dir_result(dir_path: &str) -> Result<Vec<String>> {
+    let paths = match fs::read_dir(dir_path) {
+        Ok(paths) => paths,
+        Err(err) => bail!("Failed to read directory: {}: {}", dir_path, err),
+    };
+
+    let mut res = vec![];
+    for path in paths {
+        match path {
+            Ok(path) => res.push(path.path().display().to_string()),
+            Err(err) => bail!("Failed to read entry of: {}: {}", dir_path, err),
+        }
+    }
+
+    Ok(res)
+}
+
+/// List all files and sub-directories of a directory
+///
+/// Print a warning and return an empty list if the directory cannot be read.
+/// Unreadable entries are skipped with a warning, and the other entries are
+/// still returned.
 pub fn ls_dir(dir_path: &str) -> Vec<String> {
-    match fs::read_dir(dir_path) {
-        Ok(paths) => paths
-            .into_iter()
-            .filter_map(|path| match path {
-                Ok(path) => Some(path.path().display().to_string()),
-                Err(_) => None,
+    let paths = match fs::read_dir(dir_path) {
+        Ok(paths) => paths,
+        Err(err) => {
+            warning!("ls_dir: Failed to read directory: {}: {}", dir_path, err);
+            return vec![];
+        }
+    };
+
+    paths
+        .filter_map(|path| match path {
+            Ok(path) => Some(path.path().display().to_string()),
+            Err(err) => {
+                warning!("ls_dir: Skip entry of: {}: {}", dir_path, err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// List all regular files in a directory and its sub-directories.
+///
+/// Sub-directories are visited up to `max_depth` levels below `dir_path`, or
+/// without limit if `max_depth` is `None`. Symbolic links are only followed
+/// when `follow_symlinks` is set, and a directory reached again through a
+/// cycle of links is skipped. Unreadable sub-directories are skipped with a
+/// warning.
+pub fn ls_dir_recursive(
+    dir_path: &str,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+) -> Vec<String> {
+    /// Collect files of a directory at a given depth into `res`.
+    fn visit(
+        dir: &Path,
+        depth: usize,
+        max_depth: Option<usize>,
+        follow_symlinks: bool,
+        visited: &mut HashSet<PathBuf>,
+        res: &mut Vec<String>,
+    ) {
+        // Skip directories which were already visited via symbolic links
+        if follow_symlinks {
+            let canonical_dir = match fs::canonicalize(dir) {
+                Ok(canonical_dir) => canonical_dir,
+                Err(_) => dir.to_path_buf(),
+            };
+            if !visited.insert(canonical_dir) {
+                return;
+            }
+        }
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warning!("Skip directory: {}: {}", dir.display(), err);
+                return;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    warning!("Skip entry of: {}: {}", dir.display(), err);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let file_type = match follow_symlinks {
+                true => fs::metadata(&path).map(|m| m.file_type()),
+                false => entry.file_type(),
+            };
+            match file_type {
+                Ok(ft) if ft.is_file() => res.push(path.display().to_string()),
+                Ok(ft) if ft.is_dir() => {
+                    if max_depth.map_or(true, |max| depth < max) {
+                        visit(
+                            &path,
+                            depth + 1,
+                            max_depth,
+                            follow_symlinks,
+                            visited,
+                            res,
+                        )
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => warning!("Skip entry: {}: {}", path.display(), err),
+            }
+        }
+    }
+
+    let mut res = vec![];
+    let mut visited = HashSet::new();
+    let dir = Path::new(dir_path);
+    visit(dir, 0, max_depth, follow_symlinks, &mut visited, &mut res);
+    res
+}
+
+/// Get the size of a file in bytes.
+pub fn get_file_size(path: &str) -> Result<u64> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(metadata.len()),
+        Err(err) => bail!("Failed to get file size: {}: {}", path, err),
+    }
+}
+
+/// Parse a human-readable duration, such as `250ms`, `2s`, `1h30m`, or
+/// `1h 2m 3.5s`.
+///
+/// Supported units are `h`, `m`, `s`, `ms`, and `us` or `µs`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let input = s.trim();
+    if input.is_empty() {
+        bail!("Empty duration!")
+    }
+
+    let mut nanos: u64 = 0;
+    let mut remaining = input;
+    while !remaining.is_empty() {
+        let is_number = |c: char| c.is_ascii_digit() || c == '.';
+        let number_len =
+            remaining.find(|c| !is_number(c)).unwrap_or(remaining.len());
+        let (number, rest) = remaining.split_at(number_len);
+        let unit_len = rest
+            .find(|c: char| is_number(c) || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let (unit, rest) = rest.split_at(unit_len);
+
+        let unit_nanos: u64 = match unit {
+            "h" => 3_600_000_000_000,
+            "m" => 60_000_000_000,
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "us" | "µs" => 1_000,
+            "" => bail!("Missing unit of duration: {}", s),
+            _ => bail!("Unknown unit of duration: `{}` in: {}", unit, s),
+        };
+        let value = match (number.parse::<u64>(), number.parse::<f64>()) {
+            (Ok(value), _) => value.checked_mul(unit_nanos),
+            (_, Ok(value)) if value.is_finite() => {
+                // `u64::MAX as f64` is rounded up to `2^64`, which does not fit
+                // in `u64`, so that it is also rejected.
+                let value = (value * unit_nanos as f64).round();
+                ite!(value >= u64::MAX as f64, None, Some(value as u64))
+            }
+            _ => bail!("Invalid duration: {}", s),
+        };
+        nanos = match value.and_then(|value| nanos.checked_add(value)) {
+            Some(nanos) => nanos,
+            None => bail!("Duration is too large: {}", s),
+        };
+        remaining = rest.trim_start();
+    }
+
+    Ok(Duration::from_nanos(nanos))
+}
+
+/// Get the number of logical CPUs available to the current process.
+///
+/// Return `1` if the number cannot be determined.
+pub fn get_num_cpus() -> usize {
+    thread::available_parallelism().map(Into::into).unwrap_or(1)
+}
+
+/// Run tasks in parallel by a pool of at most `max_concurrency` threads, or of
+/// `get_num_cpus()` threads if `max_concurrency` is `0`.
+///
+/// Return the results in the same order as the input tasks. A panic of any
+/// task is propagated after all threads finish.
+pub fn run_parallel<T, F>(tasks: Vec<F>, max_concurrency: usize) -> Vec<T>
+where
+    T: Send,
+    F: Fn() -> T + Send + Sync,
+{
+    let num_workers = match max_concurrency {
+        0 => get_num_cpus(),
+        n => n,
+    }
+    .min(tasks.len());
+    let next_task = AtomicUsize::new(0);
+
+    let mut results = thread::scope(|scope| {
+        let workers: Vec<_> = (0..num_workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next_task.fetch_add(1, Ordering::Relaxed);
+                        match tasks.get(index) {
+                            Some(task) => results.push((index, task())),
+                            None => break results,
+                        }
+                    }
+                })
             })
-            .collect(),
-        Err(_) => vec![],
+            .collect();
+
+        let mut results = vec![];
+        for worker in workers {
+            match worker.join() {
+                Ok(outputs) => results.extend(outputs),
+                Err(err) => panic::resume_unwind(err),
+            }
+        }
+        results
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Get the total physical memory in bytes.
+///
+/// Return an error if the current operating system is not supported.
+pub fn get_total_memory() -> Result<u64> {
+    match env::consts::OS {
+        os::LINUX => get_total_memory_linux(),
+        os::MACOS => get_total_memory_macos(),
+        #[cfg(windows)]
+        os::WINDOWS => get_total_memory_windows(),
+        os => bail!("get_total_memory: need to support: {}", os),
+    }
+}
+
+/// Get the total physical memory in bytes from `/proc/meminfo` on Linux.
+fn get_total_memory_linux() -> Result<u64> {
+    let meminfo = match fs::read_to_string("/proc/meminfo") {
+        Ok(meminfo) => meminfo,
+        Err(err) => bail!("Failed to read /proc/meminfo: {}", err),
+    };
+    let total_kb = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|line| line.trim().strip_suffix("kB"))
+        .and_then(|size| size.trim().parse::<u64>().ok());
+    match total_kb {
+        Some(size) => Ok(size * 1024),
+        None => bail!("Total memory not found in /proc/meminfo!"),
+    }
+}
+
+/// Get the total physical memory in bytes from `sysctl` on macOS.
+fn get_total_memory_macos() -> Result<u64> {
+    let mut command = process::Command::new("sysctl");
+    command.args(["-n", "hw.memsize"]);
+    let output = run_query_command(command)?;
+    match output.stdout.trim().parse::<u64>() {
+        Ok(size) if output.status == 0 => Ok(size),
+        _ => bail!("Failed to get total memory: {}", output.stderr.trim()),
+    }
+}
+
+/// Get the total physical memory in bytes from the Windows API.
+#[cfg(windows)]
+fn get_total_memory_windows() -> Result<u64> {
+    use winapi::um::sysinfoapi::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    // SAFETY: `MEMORYSTATUSEX` is a plain C structure, which is valid when
+    // zeroed, and `GlobalMemoryStatusEx` only writes to the given structure.
+    let mut status: MEMORYSTATUSEX = unsafe { std::mem::zeroed() };
+    status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+    match unsafe { GlobalMemoryStatusEx(&mut status) } {
+        0 => bail!("Failed to get total memory!"),
+        _ => Ok(status.ullTotalPhys),
     }
 }
 
+/// Get the host name of the current machine.
+pub fn get_hostname() -> Result<String> {
+    let hostname = get_hostname_from_os()?;
+    let hostname = hostname.trim_end_matches('\0').trim();
+    match hostname.is_empty() {
+        true => bail!("Host name not found!"),
+        false => Ok(hostname.to_owned()),
+    }
+}
+
+/// Get the host name of the current machine by `gethostname` on Unix.
+#[cfg(unix)]
+fn get_hostname_from_os() -> Result<String> {
+    let mut buffer = vec![0u8; 256];
+    // SAFETY: `gethostname` writes at most `buffer.len()` bytes to `buffer`.
+    let res = unsafe {
+        libc::gethostname(
+            buffer.as_mut_ptr() as *mut libc::c_char,
+            buffer.len(),
+        )
+    };
+    if res != 0 {
+        bail!("Failed to get host name: {}", io::Error::last_os_error())
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Ok(String::from_utf8_lossy(&buffer[..len]).into_owned())
+}
+
+/// Get the host name of the current machine by `GetComputerNameW` on Windows.
+#[cfg(windows)]
+fn get_hostname_from_os() -> Result<String> {
+    use winapi::um::winbase::{GetComputerNameW, MAX_COMPUTERNAME_LENGTH};
+
+    let mut buffer = vec![0u16; MAX_COMPUTERNAME_LENGTH as usize + 1];
+    let mut len = buffer.len() as u32;
+    // SAFETY: `GetComputerNameW` writes at most `len` characters to `buffer`,
+    // and then stores the number of written characters to `len`.
+    match unsafe { GetComputerNameW(buffer.as_mut_ptr(), &mut len) } {
+        0 => bail!("Failed to get host name: {}", io::Error::last_os_error()),
+        _ => Ok(String::from_utf16_lossy(&buffer[..len as usize])),
+    }
+}
+
+/// Get the host name of the current machine on unsupported platforms.
+#[cfg(not(any(unix, windows)))]
+fn get_hostname_from_os() -> Result<String> {
+    bail!("get_hostname: need to support: {}", env::consts::OS)
+}
+
+/// Get the value of an environment variable.
+///
+/// Return `None` if the variable is not set or not valid Unicode. An empty
+/// value is still returned as `Some`.
+pub fn get_env_var(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Get the value of an environment variable, or a default value if the
+/// variable is not set.
+pub fn get_env_var_or(name: &str, default: &str) -> String {
+    get_env_var(name).unwrap_or_else(|| default.to_owned())
+}
+
+/// Data structure to restore environment variables when it is dropped.
+///
+/// Variables which were not set when they are recorded will be unset.
+#[derive(Debug)]
+pub struct EnvGuard {
+    /// Names and previous values of recorded variables.
+    saved_vars: Vec<(String, Option<OsString>)>,
+}
+
+impl EnvGuard {
+    /// Constructor, recording the current values of the given variables.
+    pub fn new(names: &[&str]) -> Self {
+        let mut guard = EnvGuard { saved_vars: vec![] };
+        for name in names {
+            guard.record(name);
+        }
+        guard
+    }
+
+    /// Record the current value of a variable, if it is not yet recorded.
+    fn record(&mut self, name: &str) {
+        if !self.saved_vars.iter().any(|(saved, _)| saved == name) {
+            self.saved_vars.push((name.to_owned(), env::var_os(name)));
+        }
+    }
+
+    /// Set a variable, which is restored when the guard is dropped.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.record(name);
+        env::set_var(name, value)
+    }
+
+    /// Unset a variable, which is restored when the guard is dropped.
+    pub fn remove(&mut self, name: &str) {
+        self.record(name);
+        env::remove_var(name)
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (name, value) in self.saved_vars.iter().rev() {
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+    }
+}
+
+/// Get the home directory of the current user.
+///
+/// Read `HOME` on Unix and `USERPROFILE` on Windows, falling back to the other
+/// variable, and then `HOMEDRIVE` and `HOMEPATH` on Windows.
+pub fn get_home_directory() -> Result<String> {
+    let (primary, secondary) = match is_window_os() {
+        true => ("USERPROFILE", "HOME"),
+        false => ("HOME", "USERPROFILE"),
+    };
+    let non_empty = |name| get_env_var(name).filter(|v| !v.is_empty());
+
+    if let Some(dir) = non_empty(primary).or_else(|| non_empty(secondary)) {
+        return Ok(dir);
+    }
+    match (non_empty("HOMEDRIVE"), non_empty("HOMEPATH")) {
+        (Some(drive), Some(path)) if is_window_os() => Ok(drive + &path),
+        _ => bail!("Home directory not found!"),
+    }
+}
+
+/// Get the name of the current user, without spawning any process.
+///
+/// Read `USER` and then `LOGNAME` on Unix, and `USERNAME` on Windows.
+pub fn get_username() -> Result<String> {
+    let names: &[&str] = match is_window_os() {
+        true => &["USERNAME"],
+        false => &["USER", "LOGNAME"],
+    };
+    let username = names
+        .iter()
+        .filter_map(|name| get_env_var(name))
+        .find(|v| !v.is_empty());
+    match username {
+        Some(username) => Ok(username),
+        None => bail!("User name not found in: {}", names.join(", ")),
+    }
+}
+
+/// Expand a leading `~` and all `$VAR` or `${VAR}` occurrences of a path.
+///
+/// A leading `~` is replaced by the home directory, and variables are replaced
+/// by their values in the environment. Unknown variables are left untouched.
+pub fn expand_path(path: &str) -> String {
+    let path = match (path.strip_prefix('~'), get_home_directory()) {
+        (Some(rest), Ok(home))
+            if rest.is_empty() || rest.starts_with(std::path::is_separator) =>
+        {
+            home + rest
+        }
+        _ => path.to_owned(),
+    };
+
+    let mut res = String::new();
+    let mut remaining = path.as_str();
+    while let Some(idx) = remaining.find('$') {
+        res.push_str(&remaining[..idx]);
+        let var = &remaining[idx + 1..];
+
+        // Find the variable name and the length of its reference after `$`.
+        let (name, len) = match var.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = var
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(var.len());
+                (&var[..end], end)
+            }
+        };
+
+        match get_env_var(name).filter(|_| !name.is_empty()) {
+            Some(value) => res.push_str(&value),
+            None => res.push_str(&remaining[idx..idx + 1 + len]),
+        }
+        remaining = &var[len..];
+    }
+    res.push_str(remaining);
+
+    res
+}
+
 /// Get the current working directory.
 pub fn get_current_directory() -> Result<String> {
     match std::env::current_dir() {
@@ -69,3 +835,57 @@ pub fn get_current_directory() -> Result<String> {
         Err(err) => bail!(err),
     }
 }
+
+/// Data structure to measure the elapsed time of named phases.
+#[derive(Clone, Debug)]
+pub struct Stopwatch {
+    /// Time when the stopwatch started.
+    start: Instant,
+
+    /// Time when the last lap ended.
+    last_lap: Instant,
+
+    /// Labels and durations of recorded laps.
+    laps: Vec<(String, Duration)>,
+}
+
+impl Stopwatch {
+    /// Constructor, starting a new stopwatch.
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Stopwatch {
+            start: now,
+            last_lap: now,
+            laps: vec![],
+        }
+    }
+
+    /// Get the elapsed time since the stopwatch started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Record a lap, which lasts from the end of the previous lap, or the
+    /// start of the stopwatch, until now.
+    pub fn lap(&mut self, label: &str) {
+        let now = Instant::now();
+        self.laps.push((label.to_owned(), now - self.last_lap));
+        self.last_lap = now;
+    }
+
+    /// Get the labels and durations of all recorded laps.
+    pub fn laps(&self) -> &[(String, Duration)] {
+        &self.laps
+    }
+
+    /// Report all recorded laps, one per line.
+    pub fn report(&self) -> String {
+        self.laps
+            .iter()
+            .map(|(label, d)| {
+                format!("{}: {}", label, print::format_duration(*d))
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,38 @@
+//! Module containing common traits, used by all packages.
+
+use std::fmt::Display;
+
+use crate::print;
+
+/// Trait to print a data structure to an indentation-aware string.
+pub trait PrettyPrint {
+    /// Print to a string whose lines are indented by `indent` spaces.
+    fn pretty_print(&self, indent: usize) -> String;
+
+    /// Print to a string without indentation.
+    fn pretty(&self) -> String {
+        self.pretty_print(0)
+    }
+}
+
+impl<T: Display> PrettyPrint for T {
+    fn pretty_print(&self, indent: usize) -> String {
+        print::indent_lines(&self.to_string(), indent)
+    }
+}
+
+/// Trait of data structures carrying a name.
+///
+/// The name is the base name to be indexed in a `NamingEnv`:
+/// `NamingEnv::create_new_name_index` creates a new index of the name to rename
+/// the value uniquely, and the index is later found by
+/// `NamingEnv::get_current_index`.
+pub trait Named {
+    /// Get the name.
+    fn name(&self) -> &str;
+
+    /// Get the name qualified by a scope, in the form of `scope::name`.
+    fn qualified_name(&self, scope: &str) -> String {
+        format!("{}::{}", scope, self.name())
+    }
+}
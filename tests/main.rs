@@ -19,3 +19,1656 @@
 #![cfg_attr(feature = "linting", deny(rustdoc::broken_intra_doc_links))]
 #![cfg_attr(feature = "linting", deny(rustdoc::bare_urls))]
 //---------------------------------------------------------------------
+
+use std::{fs, path::Path};
+
+use ::anyhow::Result;
+
+/// Create a file of a given content, and its parent directories, in a
+/// directory.
+///
+/// Return the path of the created file.
+fn create_file(dir: &Path, name: &str, content: &str) -> Result<String> {
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, content)?;
+    Ok(path.display().to_string())
+}
+
+/// Environment variable marking a test run in a child process.
+const CHILD_TEST: &str = "RUTIL_TEST_CHILD";
+
+/// Check if the current test is run in a child process by
+/// `run_test_in_child_process`.
+fn is_child_process() -> bool {
+    std::env::var_os(CHILD_TEST).is_some()
+}
+
+/// Run only the test `name` in a child process of the test binary, where each
+/// environment variable is set to its value, or removed if it is `None`.
+///
+/// This isolates tests changing a process-wide state, such as environment
+/// variables or debugging flags, from other tests running in parallel.
+///
+/// Return the standard output and error of the child process.
+fn run_test_in_child_process(
+    name: &str,
+    envs: &[(&str, Option<&str>)],
+) -> Result<String> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(["--exact", name, "--nocapture", "--test-threads", "1"])
+        .env(CHILD_TEST, "1");
+    for (var, value) in envs {
+        match value {
+            Some(value) => command.env(var, value),
+            None => command.env_remove(var),
+        };
+    }
+    let output = command.output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || !stdout.contains("1 passed") {
+        ::anyhow::bail!("Failed to run test: {}: {}{}", name, stdout, stderr)
+    }
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+/// Test cases of the `anyhow` module.
+mod anyhow {
+    use ::anyhow::{anyhow, Result};
+    use rutil::anyhow::{
+        collect_results, retry, with_context, IntoResult, ResultExt,
+    };
+    use std::{io, time::Duration};
+
+    /// Fail in the first two calls, and then return the number of calls.
+    fn fail_twice(num_calls: &mut usize) -> Result<usize> {
+        *num_calls += 1;
+        match *num_calls {
+            1 | 2 => Err(anyhow!("Failure {}", num_calls)),
+            _ => Ok(*num_calls),
+        }
+    }
+
+    /// Test retrying a closure which fails twice and then succeeds.
+    #[test]
+    fn test_retry() -> Result<()> {
+        let delay = Duration::from_millis(1);
+        let mut num_calls = 0;
+        assert_eq!(retry(3, delay, || fail_twice(&mut num_calls))?, 3);
+
+        let mut num_calls = 0;
+        match retry(2, delay, || fail_twice(&mut num_calls)) {
+            Ok(_) => panic!("Retrying twice should fail"),
+            Err(err) => assert_eq!(err.to_string(), "Failure 2"),
+        }
+        Ok(())
+    }
+
+    /// Test building an `Ok` and an `Err` through `IntoResult` and attaching
+    /// a context.
+    #[test]
+    fn test_into_result_and_with_context() -> Result<()> {
+        assert_eq!(5.into_ok()?, 5);
+        let err = io::Error::new(io::ErrorKind::NotFound, "missing");
+        match err.into_err::<i32>() {
+            Ok(_) => panic!("An error should be converted to `Err`"),
+            Err(err) => assert!(err.downcast_ref::<io::Error>().is_some()),
+        }
+
+        assert_eq!(with_context(Ok(1), "Failed to parse config")?, 1);
+        let res: Result<i32> = Err(anyhow!("bad token"));
+        match with_context(res, "Failed to parse config") {
+            Ok(_) => panic!("The error should be kept"),
+            Err(err) => assert_eq!(err.to_string(), "Failed to parse config"),
+        }
+        Ok(())
+    }
+
+    /// Test collecting a mix of `Ok` and `Err` results.
+    #[test]
+    fn test_collect_results() -> Result<()> {
+        let results: Vec<Result<i32>> = vec![Ok(1), Ok(2)];
+        assert_eq!(collect_results(results)?, vec![1, 2]);
+
+        let results =
+            vec![Ok(1), Err(anyhow!("first")), Ok(3), Err(anyhow!("second"))];
+        match collect_results(results) {
+            Ok(_) => panic!("Collecting failed results should fail"),
+            Err(err) => assert_eq!(
+                err.to_string(),
+                "2 of 4 results failed:\n  - first\n  - second"
+            ),
+        }
+
+        let results: Vec<Result<i32>> =
+            (0..5).map(|i| Err(anyhow!("error {}", i))).collect();
+        match collect_results(results) {
+            Ok(_) => panic!("Collecting failed results should fail"),
+            Err(err) => {
+                let msg = err.to_string();
+                assert!(msg.starts_with("5 of 5 results failed:"));
+                assert!(msg.ends_with("  - error 2\n  - and 2 more"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Test that `ok_or_warn` turns an `Err` into `None`.
+    #[test]
+    fn test_ok_or_warn() {
+        let res: Result<i32> = Err(anyhow!("Skip unreadable file"));
+        assert_eq!(res.ok_or_warn(), None);
+        let res: Result<i32, String> = Ok(7);
+        assert_eq!(res.ok_or_warn(), Some(7));
+    }
+}
+
+/// Test cases of the `arg` module.
+mod arg {
+    use anyhow::Result;
+    use clap::{Arg, ArgMatches, Command};
+    use rutil::arg::{self, ArgExt, SubcommandCli, SubcommandHandler};
+
+    /// Create a command with two subcommands.
+    fn create_command() -> Command<'static> {
+        Command::new("tool")
+            .add_subcommand("build", "Build a project", |cmd| {
+                cmd.arg(Arg::new_argument("release"))
+            })
+            .add_subcommand("clean", "Clean a project", |cmd| cmd)
+    }
+
+    /// Handle the `build` subcommand.
+    fn handle_build(argms: &ArgMatches) -> String {
+        format!("build: {}", argms.is_present("release"))
+    }
+
+    /// Handle the `clean` subcommand.
+    fn handle_clean(_: &ArgMatches) -> String {
+        "clean".to_owned()
+    }
+
+    /// Test parsing and dispatching subcommands.
+    #[test]
+    fn test_dispatch_subcommand() -> Result<()> {
+        let handlers: Vec<(&str, SubcommandHandler<String>)> =
+            vec![("build", &handle_build), ("clean", &handle_clean)];
+
+        let argv = ["tool", "build", "--release"];
+        let argms = create_command().try_get_matches_from(argv)?;
+        let res = arg::dispatch_subcommand(&argms, &handlers);
+        assert_eq!(res, Some("build: true".to_owned()));
+
+        let argms = create_command().try_get_matches_from(["tool", "clean"])?;
+        let res = arg::dispatch_subcommand(&argms, &handlers);
+        assert_eq!(res, Some("clean".to_owned()));
+
+        let argms = create_command().try_get_matches_from(["tool"])?;
+        assert_eq!(arg::dispatch_subcommand(&argms, &handlers), None);
+        Ok(())
+    }
+}
+
+/// Test cases of the `debug` module.
+mod debug {
+    use super::{is_child_process, run_test_in_child_process};
+    use anyhow::Result;
+    use rutil::{
+        debug::{self, ColorChoice},
+        report,
+    };
+
+    /// Test that the setters and getters of global flags round-trip.
+    #[test]
+    fn test_flag_setters_and_getters() {
+        let debug_mode = debug::get_debug_mode();
+        debug::set_debug_mode(!debug_mode);
+        assert_eq!(debug::get_debug_mode(), !debug_mode);
+        debug::set_debug_mode(debug_mode);
+        assert_eq!(debug::get_debug_mode(), debug_mode);
+
+        let deep_debug_mode = debug::get_deep_debug_mode();
+        debug::set_deep_debug_mode(!deep_debug_mode);
+        assert_eq!(debug::get_deep_debug_mode(), !deep_debug_mode);
+        debug::set_deep_debug_mode(deep_debug_mode);
+
+        let marker_len = debug::get_debug_marker_len();
+        debug::set_debug_marker_len(marker_len + 4);
+        assert_eq!(debug::get_debug_marker_len(), marker_len + 4);
+        debug::set_debug_marker_len(marker_len);
+        assert_eq!(debug::get_debug_marker_len(), marker_len);
+    }
+
+    /// Test parsing color choices.
+    #[test]
+    fn test_color_choice_from_str() -> Result<()> {
+        assert_eq!("auto".parse::<ColorChoice>()?, ColorChoice::Auto);
+        assert_eq!("always".parse::<ColorChoice>()?, ColorChoice::Always);
+        assert_eq!("never".parse::<ColorChoice>()?, ColorChoice::Never);
+        match "sometimes".parse::<ColorChoice>() {
+            Ok(choice) => {
+                panic!("Invalid color choice is parsed: {:?}", choice)
+            }
+            Err(err) => assert!(err.to_string().contains("sometimes")),
+        }
+        Ok(())
+    }
+
+    /// Environment variable of the color choice set by a child test process.
+    const COLOR_CHOICE: &str = "RUTIL_TEST_COLOR_CHOICE";
+
+    /// Test that warnings are colorized according to the color choice.
+    ///
+    /// The color choice is global, so it is set by child processes running
+    /// this test, whose stdout is a pipe instead of a terminal.
+    #[test]
+    fn test_color_choice() -> Result<()> {
+        if is_child_process() {
+            let choice = std::env::var(COLOR_CHOICE)?.parse::<ColorChoice>()?;
+            debug::set_color_choice(choice);
+            assert_eq!(debug::get_color_choice(), choice);
+            let expected = choice == ColorChoice::Always;
+            assert_eq!(debug::should_colorize(), expected);
+            let text = report::colorize("text", report::ANSI_RED);
+            assert_eq!(text.contains(report::ANSI_RED), expected);
+            rutil::warning!("Colored warning");
+            return Ok(());
+        }
+
+        let test_name = "debug::test_color_choice";
+        for (choice, colorized) in
+            [("auto", false), ("always", true), ("never", false)]
+        {
+            let envs = [(COLOR_CHOICE, Some(choice))];
+            let output = run_test_in_child_process(test_name, &envs)?;
+            assert!(output.contains("Colored warning"));
+            assert_eq!(output.contains(report::ANSI_YELLOW), colorized);
+        }
+        Ok(())
+    }
+}
+
+/// Test cases of the `encoding` module.
+mod encoding {
+    use anyhow::Result;
+    use rutil::encoding;
+
+    /// Test the round trip of hex encoding and decoding.
+    #[test]
+    fn test_hex_round_trip() -> Result<()> {
+        let bytes = vec![0x00, 0x0f, 0xab, 0xff];
+        assert_eq!(encoding::to_hex(&bytes), "000fabff");
+        assert_eq!(encoding::from_hex("000fabff")?, bytes);
+        assert_eq!(encoding::from_hex("000FABFF")?, bytes);
+        assert_eq!(encoding::from_hex("")?, Vec::<u8>::new());
+        Ok(())
+    }
+
+    /// Test that invalid hex strings are rejected with a byte offset.
+    #[test]
+    fn test_from_hex_invalid() {
+        match encoding::from_hex("abc") {
+            Ok(_) => panic!("An odd length hex string is decoded"),
+            Err(err) => assert!(err.to_string().contains("odd length")),
+        }
+        match encoding::from_hex("00zz") {
+            Ok(_) => panic!("A non-hex character is decoded"),
+            Err(err) => assert!(err.to_string().contains("offset: 2")),
+        }
+        match encoding::from_hex("0é") {
+            Ok(_) => panic!("A non-ASCII character is decoded"),
+            Err(err) => {
+                let msg = err.to_string();
+                assert!(msg.contains("offset: 1"));
+                assert!(!msg.contains('é'));
+            }
+        }
+    }
+
+    /// Test the round trip of base64 encoding and decoding.
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64_round_trip() -> Result<()> {
+        assert_eq!(encoding::to_base64(b""), "");
+        assert_eq!(encoding::from_base64("")?, Vec::<u8>::new());
+        assert_eq!(encoding::to_base64(b"f"), "Zg==");
+        assert_eq!(encoding::to_base64(b"fo"), "Zm8=");
+        assert_eq!(encoding::to_base64(b"foo"), "Zm9v");
+        for len in 0..10 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+            let encoded = encoding::to_base64(&bytes);
+            assert_eq!(encoding::from_base64(&encoded)?, bytes);
+        }
+        Ok(())
+    }
+
+    /// Test that a base64 string with an invalid character is rejected.
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_from_base64_invalid() {
+        match encoding::from_base64("Zm9v!A==") {
+            Ok(_) => panic!("An invalid base64 string is decoded"),
+            Err(err) => {
+                let msg = err.to_string();
+                assert!(msg.starts_with("Invalid base64 string: "));
+                assert!(!msg.contains("Zm9v!A=="));
+            }
+        }
+    }
+}
+
+/// Test cases of the `file` module.
+mod file {
+    use super::{create_file, is_child_process, run_test_in_child_process};
+    use anyhow::Result;
+    use rutil::file;
+
+    /// Test reading back a file saved by `save_to_temporary_file`.
+    #[test]
+    fn test_read_file_saved_to_temporary_file() -> Result<()> {
+        let content = "contract C {}\n";
+        let path = file::save_to_temporary_file(content, "c.sol")?;
+        assert_eq!(file::read_file_to_string(&path)?, content);
+        assert_eq!(file::read_file_bytes(&path)?, content.as_bytes());
+        Ok(())
+    }
+
+    /// Test that the returned path of a temporary file exists and contains
+    /// the written content.
+    #[test]
+    fn test_save_to_temporary_file_path() -> Result<()> {
+        let content = "int main() { return 0; }\n";
+        let path = file::save_to_temporary_file_path(content, "main.c")?;
+        assert!(path.is_file());
+        assert!(path.ends_with("main.c"));
+        assert_eq!(std::fs::read_to_string(&path)?, content);
+        Ok(())
+    }
+
+    /// Test that reading a missing file reports its path.
+    #[test]
+    fn test_read_missing_file() {
+        let path = "/nonexistent/rutil/file.txt";
+        match file::read_file_to_string(path) {
+            Ok(_) => panic!("Reading a missing file should fail"),
+            Err(err) => assert!(err.to_string().contains(path)),
+        }
+    }
+
+    /// Test the file predicates on a file, a directory, and a broken symbolic
+    /// link.
+    #[cfg(unix)]
+    #[test]
+    fn test_file_predicates() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file_path = create_file(dir.path(), "a.txt", "a")?;
+        let dir_path = dir.path().display().to_string();
+        let link = dir.path().join("broken");
+        std::os::unix::fs::symlink(dir.path().join("missing"), &link)?;
+        let link_path = link.display().to_string();
+
+        assert!(file::file_exists(&file_path));
+        assert!(file::is_regular_file(&file_path));
+        assert!(!file::is_directory(&file_path));
+        assert!(file::file_exists(&dir_path));
+        assert!(file::is_directory(&dir_path));
+        assert!(!file::is_regular_file(&dir_path));
+        assert!(!file::file_exists(&link_path));
+        assert!(!file::is_directory(&link_path));
+        assert!(!file::is_regular_file(&link_path));
+        Ok(())
+    }
+
+    /// Test changing the extension of file names with and without extensions.
+    #[test]
+    fn test_change_extension() {
+        assert_eq!(file::change_extension("foo.c", "ll"), "foo.ll");
+        assert_eq!(file::change_extension("foo", "ll"), "foo.ll");
+        assert_eq!(file::change_extension(".bashrc", "ll"), ".bashrc.ll");
+        assert_eq!(file::change_extension("a.b.c", "ll"), "a.b.ll");
+        assert_eq!(file::change_extension("dir/foo.c", "ll"), "dir/foo.ll");
+    }
+
+    /// Test SHA-256 hashes against known vectors.
+    #[test]
+    fn test_hash_str_sha256() {
+        assert_eq!(
+            file::hash_str_sha256(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            file::hash_str_sha256("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    /// Test that hashing a file of several chunks matches hashing its
+    /// content.
+    #[test]
+    fn test_hash_file_sha256() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let content = "0123456789abcdef".repeat(file::HASH_CHUNK_SIZE / 8);
+        let path = create_file(dir.path(), "large.bc", &content)?;
+        assert_eq!(
+            file::hash_file_sha256(&path)?,
+            file::hash_str_sha256(&content)
+        );
+        let empty_path = create_file(dir.path(), "empty.bc", "")?;
+        assert_eq!(
+            file::hash_file_sha256(&empty_path)?,
+            file::hash_str_sha256("")
+        );
+        Ok(())
+    }
+
+    /// Test copying and moving files into missing directories.
+    #[test]
+    fn test_copy_and_move_file_across_directories() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let src = create_file(dir.path(), "src/a.ll", "define void @f()")?;
+        let copied = dir.path().join("out/copy/a.ll").display().to_string();
+        let moved = dir.path().join("out/move/a.ll").display().to_string();
+
+        assert_eq!(file::copy_file(&src, &copied)?, 16);
+        assert_eq!(file::read_file_to_string(&copied)?, "define void @f()");
+        assert!(file::file_exists(&src));
+
+        file::move_file(&src, &moved)?;
+        assert_eq!(file::read_file_to_string(&moved)?, "define void @f()");
+        assert!(!file::file_exists(&src));
+        Ok(())
+    }
+
+    /// Test that copying a missing file reports both paths.
+    #[test]
+    fn test_copy_missing_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let src = dir.path().join("missing.ll").display().to_string();
+        let dst = dir.path().join("dst.ll").display().to_string();
+        match file::copy_file(&src, &dst) {
+            Ok(_) => panic!("Copying a missing file should fail"),
+            Err(err) => {
+                let msg = err.to_string();
+                assert!(msg.contains(&src) && msg.contains(&dst));
+            }
+        }
+        Ok(())
+    }
+
+    /// Test that concurrent atomic writes of the same file are never observed
+    /// partially by readers.
+    #[test]
+    fn test_write_atomic_concurrently() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out/result.txt").display().to_string();
+        let contents: Vec<Vec<u8>> =
+            (0..4).map(|i| vec![b'a' + i; 64 * 1024]).collect();
+        file::write_atomic(&path, &contents[0])?;
+
+        std::thread::scope(|scope| {
+            let writers: Vec<_> = contents
+                .iter()
+                .map(|content| {
+                    scope.spawn(|| -> Result<()> {
+                        for _ in 0..10 {
+                            file::write_atomic(&path, content)?;
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            for _ in 0..100 {
+                let content = file::read_file_bytes(&path)?;
+                assert!(contents.contains(&content));
+            }
+            for writer in writers {
+                match writer.join() {
+                    Ok(res) => res?,
+                    Err(_) => panic!("Writer thread panicked"),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Test that streaming the lines of a file reconstructs its content.
+    #[test]
+    fn test_read_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let content = "line 1\nline 2\n\nline 4\n";
+        let path = create_file(dir.path(), "lines.txt", content)?;
+        let lines = file::read_lines(&path)?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(lines, vec!["line 1", "line 2", "", "line 4"]);
+        assert_eq!(lines.join("\n") + "\n", content);
+        Ok(())
+    }
+
+    /// Test counting lines of files with and without a trailing newline.
+    #[test]
+    fn test_count_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cases = [("", 0), ("\n", 1), ("a\nb\n", 2), ("a\nb", 2), ("a", 1)];
+        for (idx, (content, num_lines)) in cases.iter().enumerate() {
+            let name = format!("{}.txt", idx);
+            let path = create_file(dir.path(), &name, content)?;
+            assert_eq!(file::count_lines(&path)?, *num_lines, "{:?}", content);
+        }
+        Ok(())
+    }
+
+    /// Test that appending twice to a missing file concatenates the content.
+    #[test]
+    fn test_append_to_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("log/records.txt").display().to_string();
+        file::append_to_file(&path, "first\n")?;
+        file::append_to_file(&path, "second\n")?;
+        assert_eq!(file::read_file_to_string(&path)?, "first\nsecond\n");
+        Ok(())
+    }
+
+    /// Test finding nested `.sol` files by glob patterns.
+    #[cfg(feature = "glob")]
+    #[test]
+    fn test_glob() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let a = create_file(dir.path(), "a.sol", "")?;
+        let b = create_file(dir.path(), "lib/b.sol", "")?;
+        let c = create_file(dir.path(), "lib/nested/c.sol", "")?;
+        let c2 = create_file(dir.path(), "lib/nested/c2.sol", "")?;
+        create_file(dir.path(), "lib/readme.md", "")?;
+        let root = dir.path().display();
+
+        let pattern = format!("{}/**/*.sol", root);
+        assert_eq!(file::glob(&pattern)?, vec![a, b.clone(), c.clone(), c2]);
+        let pattern = format!("{}/lib/*.sol", root);
+        assert_eq!(file::glob(&pattern)?, vec![b]);
+        let pattern = format!("{}/lib/nested/?.sol", root);
+        assert_eq!(file::glob(&pattern)?, vec![c.clone()]);
+        let pattern = format!("{}/lib/nested/[a-c].sol", root);
+        assert_eq!(file::glob(&pattern)?, vec![c]);
+        let pattern = format!("{}/**/*.vy", root);
+        assert!(file::glob(&pattern)?.is_empty());
+        Ok(())
+    }
+
+    /// Test filtering files of directories by extensions, case-insensitively.
+    #[test]
+    fn test_ls_dir_with_ext() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let c = create_file(dir.path(), "main.c", "")?;
+        let ll = create_file(dir.path(), "main.LL", "")?;
+        create_file(dir.path(), "Makefile", "")?;
+        create_file(dir.path(), "notes.txt", "")?;
+        let nested = create_file(dir.path(), "sub/util.c", "")?;
+        let root = dir.path().display().to_string();
+
+        let mut files = file::ls_dir_with_ext(&root, &["c", "ll"]);
+        files.sort();
+        assert_eq!(files, vec![ll, c.clone()]);
+
+        let mut files =
+            file::ls_dir_recursive_with_ext(&root, &["c"], None, false);
+        files.sort();
+        assert_eq!(files, vec![c, nested]);
+        Ok(())
+    }
+
+    /// Test canonicalizing existing and nonexistent paths with `.` and `..`.
+    #[test]
+    fn test_canonicalize() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("a"))?;
+        let canonical_dir = std::fs::canonicalize(dir.path())?;
+        let expected = canonical_dir.join("a").display().to_string();
+        let root = dir.path().display();
+
+        assert_eq!(
+            file::canonicalize(&format!("{}/./a/../a", root))?,
+            expected
+        );
+        assert_eq!(file::canonicalize(&format!("{}/a", root))?, expected);
+        let path = format!("{}/./a/../a", root);
+        assert_eq!(file::canonicalize_lossy(&path), expected);
+
+        let missing = format!("{}/missing/../a/b.sol", root);
+        assert!(file::canonicalize(&missing).is_err());
+        let expected = canonical_dir.join("a").join("b.sol");
+        assert_eq!(
+            file::canonicalize_lossy(&missing),
+            expected.display().to_string()
+        );
+        Ok(())
+    }
+
+    /// Test normalizing paths lexically.
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(file::normalize_path("a/./b/../c"), "a/c");
+        assert_eq!(file::normalize_path("../x"), "../x");
+        assert_eq!(file::normalize_path("//a//b"), "/a/b");
+        assert_eq!(file::normalize_path("/../a"), "/a");
+        assert_eq!(file::normalize_path("a/.."), ".");
+    }
+
+    /// Environment variable of the output file to be written by a child test
+    /// process in the dry-run mode.
+    const DRY_RUN_OUTPUT: &str = "RUTIL_TEST_DRY_RUN_OUTPUT";
+
+    /// Test that writing a file is skipped in the dry-run mode.
+    ///
+    /// The dry-run mode is global, so the file is written by a child process
+    /// running only this test, not to affect other tests running in parallel.
+    #[test]
+    fn test_dry_run_skips_writes() -> Result<()> {
+        if is_child_process() {
+            let path = std::env::var(DRY_RUN_OUTPUT)?;
+            rutil::debug::set_dry_run(true);
+            file::write_atomic(&path, b"content")?;
+            file::append_to_file(&path, "content")?;
+            assert!(!file::file_exists(&path));
+            return Ok(());
+        }
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("output.txt").display().to_string();
+        let test_name = "file::test_dry_run_skips_writes";
+        run_test_in_child_process(test_name, &[(DRY_RUN_OUTPUT, Some(&path))])?;
+        assert!(!file::file_exists(&path));
+        Ok(())
+    }
+
+    /// Test comparing the content of files.
+    #[test]
+    fn test_files_equal() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let a = create_file(dir.path(), "a.txt", "content")?;
+        let b = create_file(dir.path(), "b.txt", "content")?;
+        let c = create_file(dir.path(), "c.txt", "CONTENT")?;
+        let d = create_file(dir.path(), "d.txt", "content!")?;
+        assert!(file::files_equal(&a, &b)?);
+        assert!(!file::files_equal(&a, &c)?);
+        assert!(!file::files_equal(&a, &d)?);
+
+        let missing = dir.path().join("missing.txt").display().to_string();
+        match file::files_equal(&a, &missing) {
+            Ok(_) => panic!("A missing file is compared"),
+            Err(err) => assert!(err.to_string().contains(&missing)),
+        }
+        Ok(())
+    }
+
+    /// Test generating a unified diff of files differing by one line.
+    #[cfg(feature = "diff")]
+    #[test]
+    fn test_diff_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let a = create_file(dir.path(), "a.txt", "one\ntwo\nthree\n")?;
+        let b = create_file(dir.path(), "b.txt", "one\nTWO\nthree\n")?;
+        let c = create_file(dir.path(), "c.txt", "one\ntwo\nthree\n")?;
+        let diff = file::diff_files(&a, &b)?;
+        let lines: Vec<&str> = diff.lines().collect();
+        assert!(lines.contains(&format!("--- {}", a).as_str()));
+        assert!(lines.contains(&format!("+++ {}", b).as_str()));
+        assert!(lines.contains(&"-two"));
+        assert!(lines.contains(&"+TWO"));
+        assert!(!lines.contains(&"-one"));
+        assert_eq!(file::diff_files(&a, &c)?, "");
+        Ok(())
+    }
+
+    /// Test that a temporary file is deleted after the function returns, even
+    /// if it returns an error.
+    #[test]
+    fn test_with_temp_file() -> Result<()> {
+        let (path, content) = file::with_temp_file("content", |path| {
+            Ok((path.to_path_buf(), std::fs::read_to_string(path)?))
+        })?;
+        assert_eq!(content, "content");
+        assert!(!path.exists());
+
+        let mut temp_path = None;
+        let res: Result<()> = file::with_temp_file("content", |path| {
+            temp_path = Some(path.to_path_buf());
+            anyhow::bail!("Failed to process file")
+        });
+        assert!(res.is_err());
+        match temp_path {
+            Some(path) => assert!(!path.exists()),
+            None => panic!("Function is not called"),
+        }
+        Ok(())
+    }
+
+    /// Test removing existing and already absent files and directories.
+    #[test]
+    fn test_remove_file_and_dir() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = create_file(dir.path(), "a.txt", "content")?;
+        file::remove_file(&path)?;
+        assert!(!file::file_exists(&path));
+        file::remove_file(&path)?;
+
+        let sub_dir = dir.path().join("sub");
+        create_file(&sub_dir.join("nested"), "b.txt", "content")?;
+        let sub_dir = sub_dir.display().to_string();
+        file::remove_dir_all(&sub_dir)?;
+        assert!(!file::file_exists(&sub_dir));
+        file::remove_dir_all(&sub_dir)?;
+        Ok(())
+    }
+
+    /// Test that a temporary workspace is removed when it is dropped.
+    #[test]
+    fn test_temp_workspace_drop() -> Result<()> {
+        let ws = file::TempWorkspace::new()?;
+        let dir = ws.path().to_path_buf();
+        let path = create_file(&dir, "sub/a.txt", "content")?;
+        assert!(file::file_exists(&path));
+        drop(ws);
+        assert!(!dir.exists());
+        Ok(())
+    }
+
+    /// Test that a persisted temporary workspace is kept after it is dropped.
+    #[test]
+    fn test_temp_workspace_persist() -> Result<()> {
+        let ws = file::TempWorkspace::new()?;
+        let path = create_file(ws.path(), "a.txt", "content")?;
+        let dir = ws.persist();
+        assert!(dir.is_dir());
+        assert_eq!(std::fs::read_to_string(&path)?, "content");
+        std::fs::remove_dir_all(&dir)?;
+        assert!(!dir.exists());
+        Ok(())
+    }
+
+    /// Test getting file stems of names with several or leading dots.
+    #[cfg(unix)]
+    #[test]
+    fn test_get_file_stem() {
+        assert_eq!(file::get_file_stem("/a/b/foo.c"), Some("foo"));
+        assert_eq!(file::get_file_stem("foo.tar.gz"), Some("foo.tar"));
+        assert_eq!(file::get_file_stem(".bashrc"), Some(".bashrc"));
+        assert_eq!(file::get_file_stem("/a/b/dir/"), Some("dir"));
+        assert_eq!(file::get_file_stem("/"), None);
+    }
+}
+
+/// Test cases of the `macros` module.
+mod macros {
+    use super::{is_child_process, run_test_in_child_process};
+    use anyhow::Result;
+    use rutil::{debug, info, ite, time_it};
+
+    /// Test that `time_it!` returns the value of the timed block.
+    #[test]
+    fn test_time_it_returns_value() {
+        let res = time_it!("Compute sum", { (1..=10).sum::<i32>() });
+        assert_eq!(res, 55);
+        let name = time_it!("Format name", format!("{}_{}", "x", 1));
+        assert_eq!(name, "x_1");
+    }
+
+    /// Test that `ite!` only evaluates the taken branch.
+    #[test]
+    fn test_ite_evaluates_only_taken_branch() {
+        let mut num_true_evals = 0;
+        let mut num_false_evals = 0;
+        let res = ite!(
+            1 < 2,
+            {
+                num_true_evals += 1;
+                "less"
+            },
+            {
+                num_false_evals += 1;
+                "greater"
+            }
+        );
+        assert_eq!(res, "less");
+        assert_eq!((num_true_evals, num_false_evals), (1, 0));
+
+        let res = ite!(1 > 2, panic!("Untaken branch is evaluated"), 0);
+        assert_eq!(res, 0);
+        let res = ite!(true, 1, panic!("Untaken branch is evaluated"));
+        assert_eq!(res, 1);
+    }
+
+    /// Environment variable of the printing flags set by a child test process,
+    /// in the form `<debug mode>,<disable printing>`.
+    const PRINTING_FLAGS: &str = "RUTIL_TEST_PRINTING_FLAGS";
+
+    /// Test that `info!` prints regardless of the debug mode, unless printing
+    /// is disabled, while `time_it!` prints only in the debug mode.
+    ///
+    /// The printing flags are global, so they are set by child processes
+    /// running this test, whose output is then checked.
+    #[test]
+    fn test_info_and_time_it_printing() -> Result<()> {
+        if is_child_process() {
+            let flags = std::env::var(PRINTING_FLAGS)?;
+            debug::set_debug_mode(flags.starts_with("true,"));
+            debug::set_disable_printing(flags.ends_with(",true"));
+            info!("Information message");
+            let res = time_it!("Timed block", { 1 + 1 });
+            assert_eq!(res, 2);
+            return Ok(());
+        }
+
+        let test_name = "macros::test_info_and_time_it_printing";
+        let cases = [
+            ("false,false", true, false),
+            ("true,false", true, true),
+            ("false,true", false, false),
+            ("true,true", false, false),
+        ];
+        for (flags, info_printed, time_printed) in cases {
+            let envs = [(PRINTING_FLAGS, Some(flags))];
+            let output = run_test_in_child_process(test_name, &envs)?;
+            assert_eq!(
+                output.contains("[inf] Information message"),
+                info_printed
+            );
+            assert_eq!(output.contains("[dbg] Timed block: "), time_printed);
+        }
+        Ok(())
+    }
+}
+
+/// Test cases of the `naming` module.
+mod naming {
+    use rutil::naming::{NamingEnv, SharedNamingEnv};
+    use std::collections::HashSet;
+
+    /// Test that fresh names created from the same base name are unique.
+    #[test]
+    fn test_fresh_name_unique() {
+        let env = NamingEnv::new();
+        let (name1, env) = env.fresh_name("x");
+        let (name2, env) = env.fresh_name("x");
+        let (name3, env) = env.fresh_name("x");
+        assert_eq!((name1.as_str(), name2.as_str()), ("x", "x_1"));
+        assert_eq!(name3, "x_2");
+
+        // Names created from a base name which is another fresh name
+        let (name4, env) = env.fresh_name("x_1");
+        let (name5, _) = env.fresh_name("x");
+        let names = [name1, name2, name3, name4, name5];
+        let unique: HashSet<&String> = names.iter().collect();
+        assert_eq!(unique.len(), names.len(), "{:?}", names);
+    }
+
+    /// Test that a fresh name skips a name created from another base name.
+    #[test]
+    fn test_fresh_name_skips_created_name() {
+        let (name1, env) = NamingEnv::new().fresh_name("x_1");
+        let (name2, env) = env.fresh_name("x");
+        let (name3, _) = env.fresh_name("x");
+        assert_eq!([name1, name2, name3], ["x_1", "x", "x_2"]);
+    }
+
+    /// Test that exiting a scope restores current indices but not counters.
+    #[test]
+    fn test_enter_and_exit_scope() {
+        let (_, env) = NamingEnv::new().fresh_name("x");
+        let (_, env) = env.fresh_name("x");
+        let saved = env.enter_scope();
+        let (inner_name, inner_env) = saved.fresh_name("x");
+        assert_eq!(inner_name, "x_2");
+        assert_eq!(inner_env.get_current_index("x"), Some(2));
+
+        let env = inner_env.exit_scope(&saved);
+        assert_eq!(env.get_current_index("x"), Some(1));
+        assert_eq!(env.naming_index_counter.get("x"), Some(&2));
+        assert_eq!(env.fresh_name("x").0, "x_3");
+    }
+
+    /// Test that names restart at index 0 after resetting an environment.
+    #[test]
+    fn test_reset_and_contains() {
+        let (_, env) = NamingEnv::new().fresh_name("x");
+        let (_, mut env) = env.fresh_name("x");
+        assert!(env.contains("x"));
+        assert!(!env.contains("y"));
+        assert_eq!(env.counter_of("x"), Some(1));
+        assert_eq!(env.counter_of("y"), None);
+
+        env.reset();
+        assert!(!env.contains("x"));
+        assert_eq!(env.counter_of("x"), None);
+        let (name, env) = env.fresh_name("x");
+        assert_eq!(name, "x");
+        assert_eq!(env.counter_of("x"), Some(0));
+    }
+
+    /// Test that an environment round-tripped through JSON continues the
+    /// sequence of fresh names.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() -> anyhow::Result<()> {
+        let (_, env) = NamingEnv::new().fresh_name("x");
+        let (_, env) = env.fresh_name("x");
+        let json = serde_json::to_string(&env)?;
+        let restored: NamingEnv = serde_json::from_str(&json)?;
+        assert_eq!(restored.get_current_index("x"), env.get_current_index("x"));
+        assert_eq!(restored.counter_of("x"), env.counter_of("x"));
+        assert_eq!(restored.fresh_name("x").0, "x_2");
+        Ok(())
+    }
+
+    /// Test that fresh names created by several threads are unique.
+    #[test]
+    fn test_shared_naming_env_threads() {
+        let env = SharedNamingEnv::new();
+        let mut names: Vec<String> = vec![];
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| -> Vec<String> {
+                        (0..100).map(|_| env.fresh_name("x")).collect()
+                    })
+                })
+                .collect();
+            for worker in workers {
+                match worker.join() {
+                    Ok(worker_names) => names.extend(worker_names),
+                    Err(_) => panic!("Worker thread panicked"),
+                }
+            }
+        });
+        let unique: HashSet<&String> = names.iter().collect();
+        assert_eq!(names.len(), 800);
+        assert_eq!(unique.len(), names.len());
+    }
+
+    /// Test that all names are sorted by names, with their current indices.
+    #[test]
+    fn test_all_names_sorted() {
+        let env = NamingEnv::new();
+        let (_, env) = env.create_new_name_index("y");
+        let (_, env) = env.create_new_name_index("b");
+        let (_, env) = env.create_new_name_index("a");
+        let (_, env) = env.create_new_name_index("b");
+        let expected = vec![
+            ("a".to_owned(), 0),
+            ("b".to_owned(), 1),
+            ("y".to_owned(), 0),
+        ];
+        assert_eq!(env.all_names(), expected);
+        assert_eq!(env.len(), 3);
+    }
+
+    /// Test that a reserved name is never returned as a fresh name.
+    #[test]
+    fn test_register_reserved() {
+        let env = NamingEnv::new().register_reserved(&["base", "tmp"]);
+        assert!(env.contains("base"));
+        let (name, env) = env.fresh_name("base");
+        assert_eq!(name, "base_1");
+        let (name, _) = env.fresh_name("tmp");
+        assert_eq!(name, "tmp_1");
+    }
+
+    /// Test that names differing only in case share a counter only in the
+    /// case-insensitive mode.
+    #[test]
+    fn test_case_insensitive() {
+        let env = NamingEnv::new_case_insensitive();
+        let (name, env) = env.fresh_name("Foo");
+        assert_eq!(name, "Foo");
+        let (name, env) = env.fresh_name("foo");
+        assert_eq!(name, "foo_1");
+        assert_eq!(env.counter_of("FOO"), Some(1));
+
+        let env = NamingEnv::new();
+        let (name, env) = env.fresh_name("Foo");
+        assert_eq!(name, "Foo");
+        let (name, env) = env.fresh_name("foo");
+        assert_eq!(name, "foo");
+        assert_eq!(env.counter_of("FOO"), None);
+    }
+
+    /// Test that merging environments takes the larger index counter of each
+    /// name, so that later fresh names do not clash.
+    #[test]
+    fn test_merge() {
+        let mut env_a = NamingEnv::new();
+        env_a.fresh_name_mut("x");
+        env_a.fresh_name_mut("x");
+        env_a.fresh_name_mut("x");
+        let mut env_b = NamingEnv::new();
+        env_b.fresh_name_mut("x");
+        env_b.fresh_name_mut("y");
+
+        for env in [env_a.merge(&env_b), env_b.merge(&env_a)] {
+            assert_eq!(env.counter_of("x"), Some(2));
+            assert_eq!(env.counter_of("y"), Some(0));
+            let (name, _) = env.fresh_name("x");
+            assert_eq!(name, "x_3");
+        }
+    }
+}
+
+/// Test cases of the `print` module.
+mod print {
+    use rutil::print;
+    use std::{collections::HashMap, time::Duration};
+
+    /// Test formatting byte sizes at the unit boundaries.
+    #[test]
+    fn test_format_byte_size() {
+        assert_eq!(print::format_byte_size(0), "0 B");
+        assert_eq!(print::format_byte_size(1023), "1023 B");
+        assert_eq!(print::format_byte_size(1024), "1.0 KiB");
+        assert_eq!(print::format_byte_size(1025), "1.0 KiB");
+        assert_eq!(print::format_byte_size(1536), "1.5 KiB");
+        assert_eq!(print::format_byte_size(1048575), "1.0 MiB");
+        assert_eq!(print::format_byte_size(1048576), "1.0 MiB");
+        assert_eq!(print::format_byte_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(print::format_byte_size(u64::MAX), "16.0 EiB");
+    }
+
+    /// Test printing vectors of strings and integers by the vector printers.
+    #[test]
+    fn test_print_vector_of_strings_and_integers() {
+        let strings = vec!["a".to_string(), "b".to_string()];
+        let integers = [1, 2, 3];
+        let quote = |s: &String| format!("`{}`", s);
+        let double = |n: &i32| (n * 2).to_string();
+        assert_eq!(print::print_vector_to_string(&strings, ", "), "a, b");
+        assert_eq!(print::print_vector_to_string(&integers, "; "), "1; 2; 3");
+        assert_eq!(
+            print::print_vector_by_printer(&strings, &quote, "\n"),
+            "`a`\n`b`"
+        );
+        assert_eq!(
+            print::print_vector_by_printer(&integers, &double, ", "),
+            "2, 4, 6"
+        );
+    }
+
+    /// Test printing bytes as hexadecimal values by a formatter closure.
+    #[test]
+    fn test_print_vector_with() {
+        let bytes: &[u8] = &[0x00, 0x1f, 0xff];
+        let res =
+            print::print_vector_with(bytes, ",", |b| format!("0x{:02X}", b));
+        assert_eq!(res, "0x00,0x1F,0xFF");
+        let empty: &[u8] = &[];
+        assert_eq!(print::print_vector_with(empty, ",", |b| b.to_string()), "");
+    }
+
+    /// Test indenting lines of a string, keeping empty lines empty.
+    #[test]
+    fn test_indent_lines_and_block() {
+        let text = "first\n\nthird";
+        assert_eq!(print::indent_block(text, 2), "    first\n\n    third");
+        assert_eq!(print::indent_lines(text, 1), " first\n\n third");
+        assert_eq!(print::indent_lines("a\n\nb\n", 2), "  a\n\n  b\n");
+        assert_eq!(print::indent_lines("", 2), "");
+    }
+
+    /// Test that numbers of a 12-element list are right-aligned.
+    #[test]
+    fn test_print_vector_numbered() {
+        let items: Vec<String> =
+            (0..12).map(|i| format!("item{}", i)).collect();
+        let res = print::print_vector_numbered(&items, 1);
+        let lines: Vec<&str> = res.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], " 1. item0");
+        assert_eq!(lines[8], " 9. item8");
+        assert_eq!(lines[9], "10. item9");
+        assert_eq!(lines[10], "11. item10");
+        assert_eq!(print::print_vector_numbered(&["a", "b"], 0), "0. a\n1. b");
+    }
+
+    /// Test aligning a table of 2 columns and 3 rows, where a short row is
+    /// padded.
+    #[test]
+    fn test_format_table() {
+        let rows = vec![
+            vec!["x".to_string(), "1".to_string()],
+            vec!["counter".to_string(), "42".to_string()],
+            vec!["y".to_string()],
+        ];
+        let expected = "name     value\n\
+                        -------  -----\n\
+                        x        1\n\
+                        counter  42\n\
+                        y";
+        assert_eq!(print::format_table(&["name", "value"], &rows), expected);
+    }
+
+    /// Test printing a small map sorted by keys.
+    #[test]
+    fn test_print_map_sorted() {
+        let map: HashMap<&str, i32> =
+            [("c", 3), ("a", 1), ("b", 2)].into_iter().collect();
+        assert_eq!(
+            print::print_map_sorted(&map, ": ", ", "),
+            "a: 1, b: 2, c: 3"
+        );
+        let single: HashMap<&str, i32> = [("k", 0)].into_iter().collect();
+        assert_eq!(print::print_map(&single, "=", "\n"), "k=0");
+    }
+
+    /// Test truncating strings with multibyte characters at the boundary.
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(print::truncate_with_ellipsis("héllo", 5), "héllo");
+        assert_eq!(print::truncate_with_ellipsis("héllo", 4), "hél…");
+        assert_eq!(print::truncate_with_ellipsis("日本語テキスト", 3), "日本…");
+        assert_eq!(print::truncate_with_ellipsis("日本語", 1), "…");
+        assert_eq!(print::truncate_with_ellipsis("日本語", 0), "");
+        let res = print::truncate_with_ellipsis("αβγδε", 4);
+        assert_eq!(res.chars().count(), 4);
+    }
+
+    /// Test formatting durations around 1 second, 1 minute, and 1 hour.
+    #[test]
+    fn test_format_duration() {
+        let cases = [
+            (Duration::from_micros(500), "500µs"),
+            (Duration::from_millis(350), "350ms"),
+            (Duration::from_millis(999), "999ms"),
+            (Duration::from_secs(1), "1.0s"),
+            (Duration::from_millis(1200), "1.2s"),
+            (Duration::from_millis(59_940), "59.9s"),
+            (Duration::from_millis(59_960), "1m"),
+            (Duration::from_secs(60), "1m"),
+            (Duration::from_secs(61), "1m 1s"),
+            (Duration::from_secs(3599), "59m 59s"),
+            (Duration::from_millis(3_599_600), "1h"),
+            (Duration::from_secs(3600), "1h"),
+            (Duration::from_secs(3723), "1h 2m 3s"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(print::format_duration(duration), expected);
+        }
+    }
+
+    /// Test that a terminator follows every element, including the last.
+    #[test]
+    fn test_print_vector_terminated() {
+        let items = vec!["a", "b", "c"];
+        let expected = items.join(";\n") + ";\n";
+        assert_eq!(print::print_vector_terminated(&items, ";\n"), expected);
+        let empty: Vec<&str> = vec![];
+        assert_eq!(print::print_vector_terminated(&empty, ";\n"), "");
+    }
+
+    /// Test printing a vector with a placeholder for the empty case.
+    #[test]
+    fn test_print_vector_or() {
+        let empty: Vec<u32> = vec![];
+        assert_eq!(print::print_vector_or(&empty, ", ", "<none>"), "<none>");
+        assert_eq!(print::print_vector_or(&[1, 2], ", ", "<none>"), "1, 2");
+    }
+
+    /// Create a tree of three levels to be rendered.
+    fn create_tree() -> print::TreeNode {
+        let leaf = |label| print::TreeNode::new(label, vec![]);
+        let a = print::TreeNode::new("a", vec![leaf("a1"), leaf("a2")]);
+        let b = print::TreeNode::new("b", vec![leaf("b1")]);
+        print::TreeNode::new("root", vec![a, b])
+    }
+
+    /// Test rendering a tree with Unicode connectors.
+    #[test]
+    fn test_tree_render() {
+        let expected = [
+            "root",
+            "├── a",
+            "│   ├── a1",
+            "│   └── a2",
+            "└── b",
+            "    └── b1",
+        ];
+        assert_eq!(create_tree().render(), expected.join("\n"));
+    }
+
+    /// Test rendering a tree with ASCII connectors.
+    #[test]
+    fn test_tree_render_ascii() {
+        let expected = [
+            "root",
+            "|-- a",
+            "|   |-- a1",
+            "|   `-- a2",
+            "`-- b",
+            "    `-- b1",
+        ];
+        assert_eq!(create_tree().render_ascii(), expected.join("\n"));
+    }
+
+    /// Test printing optional values and results.
+    #[test]
+    fn test_print_option_result() {
+        assert_eq!(print::print_option(&Some(5), "-"), "5");
+        assert_eq!(print::print_option::<u32>(&None, "-"), "-");
+        let ok: Result<u32, String> = Ok(5);
+        let err: Result<u32, String> = Err("bad input".to_owned());
+        assert_eq!(print::print_result(&ok), "5");
+        assert_eq!(print::print_result(&err), "Error: bad input");
+    }
+
+    /// Test formatting CSV with commas and embedded quotes.
+    #[test]
+    fn test_to_csv() {
+        let row = print::to_csv_row(&["plain", "a,b", "say \"hi\"", "x\ny"]);
+        assert_eq!(row, "plain,\"a,b\",\"say \"\"hi\"\"\",\"x\ny\"");
+        let rows = vec![
+            vec!["name".to_owned(), "note".to_owned()],
+            vec!["foo".to_owned(), "1,2".to_owned()],
+        ];
+        assert_eq!(print::to_csv(&rows), "name,note\r\nfoo,\"1,2\"\r\n");
+    }
+
+    /// Test formatting a Markdown table with escaped pipes and padded rows.
+    #[test]
+    fn test_to_markdown_table() {
+        let rows = vec![
+            vec!["a|b".to_owned(), "line\nbreak".to_owned()],
+            vec!["short".to_owned()],
+            vec!["crlf\r\nend".to_owned(), "cr\rend".to_owned()],
+        ];
+        let table = print::to_markdown_table(&["Name", "Note"], &rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "| Name | Note |");
+        assert_eq!(lines[1], "| --- | --- |");
+        assert_eq!(lines[2], "| a\\|b | line break |");
+        assert_eq!(lines[3], "| short |  |");
+        assert_eq!(lines[4], "| crlf end | cr end |");
+        assert_eq!(lines.len(), 5);
+        assert!(!table.contains('\r'));
+    }
+
+    /// Test formatting lists in English with the Oxford comma.
+    #[test]
+    fn test_format_list_human() {
+        let empty: Vec<&str> = vec![];
+        assert_eq!(print::format_list_human(&empty), "");
+        assert_eq!(print::format_list_human(&["a"]), "a");
+        assert_eq!(print::format_list_human(&["a", "b"]), "a and b");
+        assert_eq!(print::format_list_human(&["a", "b", "c"]), "a, b, and c");
+        let items = ["a", "b", "c", "d"];
+        assert_eq!(print::format_list_human(&items), "a, b, c, and d");
+    }
+}
+
+/// Test cases of the `system` module.
+mod system {
+    use super::{create_file, is_child_process, run_test_in_child_process};
+    use anyhow::Result;
+    use rutil::system;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    /// Test that recursive listing stops at a cycle of symbolic links.
+    #[cfg(unix)]
+    #[test]
+    fn test_ls_dir_recursive_symlink_cycle() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file_a = create_file(dir.path(), "a/f.txt", "f")?;
+        let file_b = create_file(dir.path(), "a/b/g.txt", "g")?;
+        let link = dir.path().join("a/b/loop");
+        std::os::unix::fs::symlink(dir.path().join("a"), link)?;
+
+        let root = dir.path().display().to_string();
+        let mut files = system::ls_dir_recursive(&root, None, true);
+        files.sort();
+        assert_eq!(files, vec![file_b, file_a]);
+        Ok(())
+    }
+
+    /// Test finding the path of a present and an absent command.
+    #[test]
+    fn test_path_of_command_from_env() {
+        match system::path_of_command_from_env("cargo") {
+            Ok(path) => {
+                assert!(!path.contains('\n'));
+                assert!(std::path::Path::new(&path).is_file());
+            }
+            Err(err) => panic!("Command cargo should be found: {}", err),
+        }
+        let absent = "definitely-not-a-rutil-command";
+        assert!(system::path_of_command_from_env(absent).is_err());
+    }
+
+    /// Test capturing the output of `echo`.
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command() -> Result<()> {
+        let output = system::run_command("echo", &["hello", "world"])?;
+        assert_eq!(output.status, 0);
+        assert_eq!(output.stdout, "hello world\n");
+        assert_eq!(output.stderr, "");
+
+        let output =
+            system::run_command("sh", &["-c", "echo oops >&2; exit 3"])?;
+        assert_eq!(output.status, 3);
+        assert_eq!(output.stderr, "oops\n");
+
+        let absent = "definitely-not-a-rutil-command";
+        assert!(system::run_command(absent, &[]).is_err());
+        Ok(())
+    }
+
+    /// Test that a command exceeding its time limit is killed quickly and
+    /// reported as timed out.
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_with_timeout() -> Result<()> {
+        let timeout = Duration::from_millis(200);
+        let start = Instant::now();
+        match system::run_command_with_timeout("sleep", &["5"], timeout) {
+            Ok(_) => panic!("Command should time out"),
+            Err(err) => match err.downcast_ref::<system::CommandError>() {
+                Some(system::CommandError::TimedOut(t)) => {
+                    assert_eq!(*t, timeout)
+                }
+                None => panic!("Unexpected error: {}", err),
+            },
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        let output =
+            system::run_command_with_timeout("echo", &["ok"], timeout)?;
+        assert_eq!(output.stdout, "ok\n");
+        Ok(())
+    }
+
+    /// Test expanding `~` and environment variables in paths.
+    #[test]
+    fn test_expand_path() -> Result<()> {
+        let home = system::get_home_directory()?;
+        assert_eq!(system::expand_path("~"), home);
+        assert_eq!(system::expand_path("~/foo"), format!("{}/foo", home));
+        assert_eq!(system::expand_path("~foo"), "~foo");
+
+        let home_var = match cfg!(windows) {
+            true => "USERPROFILE",
+            false => "HOME",
+        };
+        let path = format!("${{{}}}/foo", home_var);
+        assert_eq!(system::expand_path(&path), format!("{}/foo", home));
+        let path = format!("${}/foo", home_var);
+        assert_eq!(system::expand_path(&path), format!("{}/foo", home));
+
+        let unknown = "$RUTIL_UNKNOWN_VARIABLE/${RUTIL_UNKNOWN_VARIABLE}";
+        assert_eq!(system::expand_path(unknown), unknown);
+        Ok(())
+    }
+
+    /// Test resolving the first available command among candidates.
+    #[test]
+    fn test_which_any() {
+        let candidates = ["definitely-not-a-rutil-command", "cargo"];
+        assert_eq!(
+            system::which_any(&candidates),
+            system::path_of_command_from_env("cargo").ok()
+        );
+        assert!(system::which_any(&candidates).is_some());
+        assert_eq!(
+            system::which_any(&["definitely-not-a-rutil-command"]),
+            None
+        );
+    }
+
+    /// Test escaping arguments with spaces, quotes, and `$` for POSIX shells.
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_escape() {
+        assert_eq!(system::shell_escape("main.c"), "main.c");
+        assert_eq!(system::shell_escape("-I/usr/include"), "-I/usr/include");
+        assert_eq!(system::shell_escape(""), "''");
+        assert_eq!(system::shell_escape("a b"), "'a b'");
+        assert_eq!(system::shell_escape("it's"), "'it'\\''s'");
+        assert_eq!(system::shell_escape("say \"hi\""), "'say \"hi\"'");
+        assert_eq!(system::shell_escape("$HOME"), "'$HOME'");
+        assert_eq!(
+            system::shell_escape_args(&["clang", "-o", "a b.ll"]),
+            "clang -o 'a b.ll'"
+        );
+    }
+
+    /// Test escaping arguments with spaces, quotes, `$`, and `%` for
+    /// `cmd.exe`.
+    #[cfg(windows)]
+    #[test]
+    fn test_shell_escape() {
+        assert_eq!(system::shell_escape("main.c"), "main.c");
+        assert_eq!(system::shell_escape(""), "^\"^\"");
+        assert_eq!(system::shell_escape("a b"), "^\"a b^\"");
+        assert_eq!(
+            system::shell_escape("say \"hi\""),
+            "^\"say \\^\"hi\\^\"^\""
+        );
+        assert_eq!(system::shell_escape("$HOME"), "^\"$HOME^\"");
+        assert_eq!(system::shell_escape("%PATH%"), "^\"^%PATH^%^\"");
+        assert_eq!(system::shell_escape("a&b"), "^\"a^&b^\"");
+    }
+
+    /// Test that extra environment variables are only set for the command.
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_with_env() -> Result<()> {
+        let name = "RUTIL_TEST_RUN_COMMAND_WITH_ENV";
+        let script = format!("echo ${}", name);
+        let output = system::run_command_with_env(
+            "sh",
+            &["-c", &script],
+            &[(name, "bar")],
+        )?;
+        assert_eq!(output.stdout, "bar\n");
+        assert_eq!(system::get_env_var(name), None);
+        Ok(())
+    }
+
+    /// Test running `pwd` in a temporary directory.
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_in_dir() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cwd = dir.path().display().to_string();
+        let output = system::run_command_in_dir("pwd", &["-P"], &cwd)?;
+        let expected = std::fs::canonicalize(dir.path())?;
+        assert_eq!(output.stdout.trim_end(), expected.display().to_string());
+        assert_ne!(system::get_current_directory()?, cwd);
+        Ok(())
+    }
+
+    /// Test getting the size of a file of a known length.
+    #[test]
+    fn test_get_file_size() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = create_file(dir.path(), "data.bin", &"x".repeat(1234))?;
+        assert_eq!(system::get_file_size(&path)?, 1234);
+
+        let missing = dir.path().join("missing.bin").display().to_string();
+        match system::get_file_size(&missing) {
+            Ok(_) => panic!("Getting the size of a missing file should fail"),
+            Err(err) => assert!(err.to_string().contains(&missing)),
+        }
+        Ok(())
+    }
+
+    /// Test that the total physical memory is found.
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    #[test]
+    fn test_get_total_memory() -> Result<()> {
+        assert!(system::get_total_memory()? > 0);
+        Ok(())
+    }
+
+    /// Test that the host name is found and nonempty.
+    #[test]
+    fn test_get_hostname() -> Result<()> {
+        let hostname = system::get_hostname()?;
+        assert!(!hostname.is_empty());
+        assert_eq!(hostname, hostname.trim());
+        Ok(())
+    }
+
+    /// Test recording laps of a stopwatch and reporting them.
+    #[test]
+    fn test_stopwatch() {
+        let mut stopwatch = system::Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(10));
+        stopwatch.lap("parse");
+        stopwatch.lap("analyze");
+        let laps = stopwatch.laps();
+        assert_eq!(laps.len(), 2);
+        assert_eq!(laps[0].0, "parse");
+        assert_eq!(laps[1].0, "analyze");
+        assert!(laps[0].1 >= Duration::from_millis(10));
+        assert!(stopwatch.elapsed() >= laps[0].1 + laps[1].1);
+        let report = stopwatch.report();
+        assert_eq!(report.lines().count(), 2);
+        assert!(report.contains("parse: "));
+        assert!(report.contains("analyze: "));
+    }
+
+    /// Test parsing valid and compound durations.
+    #[test]
+    fn test_parse_duration() -> Result<()> {
+        let parse = system::parse_duration;
+        assert_eq!(parse("250ms")?, Duration::from_millis(250));
+        assert_eq!(parse("2s")?, Duration::from_secs(2));
+        assert_eq!(parse("1.5s")?, Duration::from_millis(1500));
+        assert_eq!(parse("10us")?, Duration::from_micros(10));
+        assert_eq!(parse("10µs")?, Duration::from_micros(10));
+        assert_eq!(parse("1h30m")?, Duration::from_secs(5400));
+        assert_eq!(parse(" 1h 2m 3.5s ")?, Duration::from_millis(3_723_500));
+        Ok(())
+    }
+
+    /// Test that invalid or too large durations are rejected.
+    #[test]
+    fn test_parse_duration_invalid() {
+        let cases = [
+            ("", "Empty duration"),
+            ("5", "Missing unit"),
+            ("5d", "Unknown unit"),
+            ("1.2.3s", "Invalid duration"),
+            ("99999999999999999999999h", "Duration is too large"),
+            ("18446744073709551615s", "Duration is too large"),
+            ("10000000h 10000000h", "Duration is too large"),
+        ];
+        for (input, expected) in cases {
+            match system::parse_duration(input) {
+                Ok(d) => panic!("Duration is parsed: {}: {:?}", input, d),
+                Err(err) => assert!(err.to_string().contains(expected)),
+            }
+        }
+    }
+
+    /// Test running tasks in parallel by a bounded pool of threads.
+    #[test]
+    fn test_run_parallel() {
+        let active = AtomicUsize::new(0);
+        let max_active = AtomicUsize::new(0);
+        let tasks: Vec<_> = (0..10)
+            .map(|idx| {
+                let (active, max_active) = (&active, &max_active);
+                move || {
+                    let num = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_active.fetch_max(num, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    idx * idx
+                }
+            })
+            .collect();
+        let res = system::run_parallel(tasks, 2);
+        let expected: Vec<usize> = (0..10).map(|idx| idx * idx).collect();
+        assert_eq!(res, expected);
+        assert!(max_active.load(Ordering::SeqCst) <= 2);
+    }
+
+    /// Environment variable of the user name expected by a child test process,
+    /// which expects an error if it is not set.
+    const EXPECTED_USERNAME: &str = "RUTIL_TEST_EXPECTED_USERNAME";
+
+    /// Test getting the user name from `USER`, and then from `LOGNAME`.
+    ///
+    /// The variables are set only for child processes running this test, not
+    /// to affect other tests running in parallel.
+    #[cfg(unix)]
+    #[test]
+    fn test_get_username() -> Result<()> {
+        if is_child_process() {
+            match system::get_env_var(EXPECTED_USERNAME) {
+                Some(expected) => assert_eq!(system::get_username()?, expected),
+                None => assert!(system::get_username().is_err()),
+            }
+            return Ok(());
+        }
+
+        let test_name = "system::test_get_username";
+        let cases = [
+            (None, Some("logname"), Some("logname")),
+            (Some("user"), Some("logname"), Some("user")),
+            (Some(""), Some("logname"), Some("logname")),
+            (Some(""), None, None),
+        ];
+        for (user, logname, expected) in cases {
+            let envs = [
+                ("USER", user),
+                ("LOGNAME", logname),
+                (EXPECTED_USERNAME, expected),
+            ];
+            run_test_in_child_process(test_name, &envs)?;
+        }
+        Ok(())
+    }
+
+    /// Test that environment variables are restored when a guard is dropped.
+    #[test]
+    fn test_env_guard() {
+        let (changed, removed, added) = (
+            "RUTIL_TEST_ENV_GUARD_CHANGED",
+            "RUTIL_TEST_ENV_GUARD_REMOVED",
+            "RUTIL_TEST_ENV_GUARD_ADDED",
+        );
+        std::env::set_var(changed, "old");
+        std::env::set_var(removed, "old");
+        std::env::remove_var(added);
+        {
+            let mut guard = system::EnvGuard::new(&[changed]);
+            guard.set(changed, "new");
+            guard.remove(removed);
+            guard.set(added, "new");
+            assert_eq!(system::get_env_var(changed).as_deref(), Some("new"));
+            assert_eq!(system::get_env_var(removed), None);
+            assert_eq!(system::get_env_var(added).as_deref(), Some("new"));
+        }
+        assert_eq!(system::get_env_var(changed).as_deref(), Some("old"));
+        assert_eq!(system::get_env_var(removed).as_deref(), Some("old"));
+        assert_eq!(system::get_env_var(added), None);
+        std::env::remove_var(changed);
+        std::env::remove_var(removed);
+    }
+
+    /// Test that a variable set to an empty string is considered as present.
+    #[test]
+    fn test_get_env_var_empty() {
+        let name = "RUTIL_TEST_GET_ENV_VAR_EMPTY";
+        let mut guard = system::EnvGuard::new(&[name]);
+        guard.set(name, "");
+        assert_eq!(system::get_env_var(name).as_deref(), Some(""));
+        assert_eq!(system::get_env_var_or(name, "d"), "");
+        guard.remove(name);
+        assert_eq!(system::get_env_var(name), None);
+        assert_eq!(system::get_env_var_or(name, "d"), "d");
+    }
+}